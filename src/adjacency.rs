@@ -0,0 +1,315 @@
+use crate::{ParseError, ReversibleVGraph, VGraph};
+use std::collections::HashMap;
+use std::fmt::{Display, Write};
+use std::hash::Hash;
+use std::str::FromStr;
+
+/// A concrete graph stored as a list of outgoing, weighted edges per node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdjacencyListGraph<N, D>
+where
+    N: Eq + Hash,
+{
+    edges: HashMap<N, Vec<(N, D)>>,
+    // The sources of the edges into each node.
+    reverse: HashMap<N, Vec<N>>,
+}
+
+impl<N, D> AdjacencyListGraph<N, D>
+where
+    N: Eq + Hash + Copy,
+{
+    pub fn new() -> Self {
+        AdjacencyListGraph {
+            edges: HashMap::new(),
+            reverse: HashMap::new(),
+        }
+    }
+
+    /// Adds a node without any edges. Does nothing if the node is already present.
+    pub fn add_node(&mut self, node: N) {
+        self.edges.entry(node).or_default();
+    }
+
+    /// Adds a directed edge, adding either node if it is not present yet.
+    pub fn add_edge(&mut self, from: N, to: N, dist: D) {
+        self.add_node(to);
+        self.edges.entry(from).or_default().push((to, dist));
+        self.reverse.entry(to).or_default().push(from);
+    }
+
+    /// Every node in the graph, in no particular order.
+    pub fn nodes(&self) -> impl Iterator<Item = N> + '_ {
+        self.edges.keys().copied()
+    }
+
+    /// The outgoing edges of `node` along with their distances.
+    pub fn edges_from(&self, node: N) -> &[(N, D)] {
+        self.edges.get(&node).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn num_edges(&self) -> usize {
+        self.edges.values().map(Vec::len).sum()
+    }
+}
+
+impl<N, D> AdjacencyListGraph<N, D>
+where
+    N: Eq + Hash + Copy,
+    D: Copy,
+{
+    /// Every edge as `(from, to, dist)`, in no particular order. Nodes without any edges are
+    /// not included.
+    pub fn to_edge_list(&self) -> Vec<(N, N, D)> {
+        self.edges
+            .iter()
+            .flat_map(|(&from, edges)| edges.iter().map(move |&(to, dist)| (from, to, dist)))
+            .collect()
+    }
+
+    /// A graph with exactly the given edges.
+    pub fn from_edge_list<I>(edges: I) -> Self
+    where
+        I: IntoIterator<Item = (N, N, D)>,
+    {
+        let mut g = AdjacencyListGraph::new();
+        for (from, to, dist) in edges {
+            g.add_edge(from, to, dist);
+        }
+        g
+    }
+
+    /// The graph as text, one `from to dist` edge per line, followed by a line with just the
+    /// node for each node without any edges. `read_edges` reads it back.
+    pub fn write_edges(&self) -> String
+    where
+        N: Display,
+        D: Display,
+    {
+        let mut text = String::new();
+        for (from, to, dist) in self.to_edge_list() {
+            writeln!(text, "{from} {to} {dist}").expect("Writing to a String can not fail.");
+        }
+        for (&node, edges) in &self.edges {
+            if edges.is_empty() && !self.reverse.contains_key(&node) {
+                writeln!(text, "{node}").expect("Writing to a String can not fail.");
+            }
+        }
+        text
+    }
+
+    /// Reads a graph written by `write_edges`.
+    ///
+    /// Fields are separated by any whitespace. Blank lines and lines starting with `#` are
+    /// skipped.
+    pub fn read_edges(text: &str) -> Result<Self, ParseError>
+    where
+        N: FromStr,
+        D: FromStr,
+    {
+        let mut g = AdjacencyListGraph::new();
+        for (i, content) in text.lines().enumerate() {
+            let line = i + 1;
+            let content = content.trim();
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+            let node = |text: &str| {
+                text.parse().map_err(|_| ParseError::InvalidNode {
+                    line,
+                    text: text.to_string(),
+                })
+            };
+            match content.split_whitespace().collect::<Vec<_>>()[..] {
+                [alone] => g.add_node(node(alone)?),
+                [from, to, dist] => {
+                    let dist = dist.parse().map_err(|_| ParseError::InvalidDist {
+                        line,
+                        text: dist.to_string(),
+                    })?;
+                    g.add_edge(node(from)?, node(to)?, dist);
+                }
+                ref fields => {
+                    return Err(ParseError::WrongFieldCount {
+                        line,
+                        found: fields.len(),
+                    })
+                }
+            }
+        }
+        Ok(g)
+    }
+}
+
+impl<N, D> Default for AdjacencyListGraph<N, D>
+where
+    N: Eq + Hash + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N, D> VGraph for AdjacencyListGraph<N, D>
+where
+    N: Eq + Hash + Copy,
+    D: Copy,
+{
+    type Node = N;
+
+    type Dist = D;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.edges_from(node).iter().map(|&(to, _)| to).collect()
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        self.edges_from(from)
+            .iter()
+            .find(|&&(next, _)| next == to)
+            .map(|&(_, dist)| dist)
+            .expect("dist is only defined for nodes connected by an edge.")
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.num_nodes())
+    }
+}
+
+impl<N, D> ReversibleVGraph for AdjacencyListGraph<N, D>
+where
+    N: Eq + Hash + Copy,
+    D: Copy,
+{
+    fn in_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.reverse.get(&node).cloned().unwrap_or_default()
+    }
+}
+
+/// Copies every node reachable from `start`, and every edge out of them, into an
+/// `AdjacencyListGraph`.
+///
+/// Calls `out_edges` once per node and `dist` once per edge, so a graph that is expensive to
+/// compute can afterward be searched many times at the cost of a lookup. The reachable part of
+/// the graph must be finite, otherwise this never returns.
+pub fn snapshot<G>(g: G, start: G::Node) -> AdjacencyListGraph<G::Node, G::Dist>
+where
+    G: VGraph,
+    G::Node: Eq + Hash + Copy,
+{
+    let mut copy = AdjacencyListGraph::new();
+    copy.add_node(start);
+    let mut to_copy = vec![start];
+    while let Some(cur) = to_copy.pop() {
+        for next in g.out_edges(cur) {
+            // Nodes are only added once they are reached, so a new one still needs copying.
+            if !copy.edges.contains_key(&next) {
+                to_copy.push(next);
+            }
+            copy.add_edge(cur, next, g.dist(cur, next));
+        }
+    }
+    copy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::Cycles;
+    use crate::{a_star_search, dijkstra_iter};
+
+    #[test]
+    fn a_star_search_on_adjacency_list() {
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(1, 2, 5);
+        g.add_edge(1, 3, 1);
+        g.add_edge(3, 2, 1);
+        g.add_node(4);
+        assert_eq!(4, g.num_nodes());
+        assert_eq!(3, g.num_edges());
+        assert_eq!(Some(vec![1, 3, 2]), a_star_search(&g, 1, |n| n == 2, |_| 0));
+        assert_eq!(None, a_star_search(&g, 1, |n| n == 4, |_| 0));
+    }
+
+    #[test]
+    fn in_edges_mirror_out_edges() {
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(1, 2, 5);
+        g.add_edge(3, 2, 1);
+        assert_eq!(vec![1, 3], g.in_edges(2));
+        assert!(g.in_edges(1).is_empty());
+    }
+
+    #[test]
+    fn snapshot_searches_like_the_original() {
+        let copy = snapshot(Cycles {}, 1);
+        // 7, 8 and 9 cannot be reached from 1.
+        assert_eq!(7, copy.num_nodes());
+        assert_eq!(11, copy.num_edges());
+        for end in 1..=10 {
+            assert_eq!(
+                a_star_search(Cycles {}, 1, |n| n == end, |_| 0),
+                a_star_search(&copy, 1, |n| n == end, |_| 0)
+            );
+        }
+        let mut original: Vec<_> = dijkstra_iter(Cycles {}, 4).collect();
+        let mut copied: Vec<_> = dijkstra_iter(&copy, 4).collect();
+        original.sort();
+        copied.sort();
+        assert_eq!(original, copied);
+    }
+
+    #[test]
+    fn edge_list_round_trip() {
+        let mut g = snapshot(Cycles {}, 1);
+        g.add_node(11);
+        let mut edges = g.to_edge_list();
+        edges.sort();
+        let mut rebuilt = AdjacencyListGraph::from_edge_list(edges.clone()).to_edge_list();
+        rebuilt.sort();
+        assert_eq!(edges, rebuilt);
+
+        let read: AdjacencyListGraph<usize, usize> =
+            AdjacencyListGraph::read_edges(&g.write_edges()).unwrap();
+        let mut read_edges = read.to_edge_list();
+        read_edges.sort();
+        assert_eq!(edges, read_edges);
+        assert!(read.nodes().all(|n| g.edges.contains_key(&n)));
+        assert_eq!(8, read.num_nodes());
+    }
+
+    #[test]
+    fn reads_and_reports_errors() {
+        let text = "# from to dist\n1 2 5\n\n  2\t3 1  \n4\n";
+        let g: AdjacencyListGraph<u32, u32> = AdjacencyListGraph::read_edges(text).unwrap();
+        assert_eq!(4, g.num_nodes());
+        assert_eq!(vec![3], g.out_edges(2));
+        assert_eq!(5, g.dist(1, 2));
+
+        let read = |text| AdjacencyListGraph::<u32, u32>::read_edges(text);
+        assert_eq!(
+            Err(ParseError::WrongFieldCount { line: 2, found: 2 }),
+            read("1 2 3\n1 2")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidNode {
+                line: 1,
+                text: "a".to_string()
+            }),
+            read("a 2 3")
+        );
+        let err = read("1 2 -3").unwrap_err();
+        assert_eq!(
+            ParseError::InvalidDist {
+                line: 1,
+                text: "-3".to_string()
+            },
+            err
+        );
+        assert_eq!("line 1: \"-3\" is not a valid distance", err.to_string());
+    }
+}
@@ -0,0 +1,123 @@
+//! Synthetic graphs for benchmarking and testing searches on consistent inputs.
+use crate::{AdjacencyListGraph, GridGraph};
+use std::collections::HashSet;
+
+/// An open `width` by `height` grid with unit cost moves.
+pub fn grid_graph(width: usize, height: usize) -> GridGraph {
+    GridGraph::new(width, height)
+}
+
+/// A strongly connected random graph over the nodes `0..n`.
+///
+/// Nodes are first joined by a random spanning tree with edges in both directions, then random
+/// directed edges are added until there are `n * avg_degree` edges in total. The graph has no
+/// self loops or repeated edges, so the edge count is capped at `n * (n - 1)`. Edge distances
+/// are between 1 and 100. The same `seed` always produces the same graph.
+pub fn random_graph(n: usize, avg_degree: usize, seed: u64) -> AdjacencyListGraph<usize, u32> {
+    let mut rng = SplitMix64::new(seed);
+    let mut g = AdjacencyListGraph::new();
+    let mut edges = HashSet::new();
+    let mut add_edge = |g: &mut AdjacencyListGraph<usize, u32>, rng: &mut SplitMix64, from, to| {
+        if from != to && edges.insert((from, to)) {
+            g.add_edge(from, to, 1 + rng.below(100) as u32);
+        }
+    };
+
+    for node in 0..n {
+        g.add_node(node);
+        if node > 0 {
+            let parent = rng.below(node as u64) as usize;
+            add_edge(&mut g, &mut rng, node, parent);
+            add_edge(&mut g, &mut rng, parent, node);
+        }
+    }
+
+    let target = (n * avg_degree).min(n * n.saturating_sub(1));
+    while g.num_edges() < target {
+        let from = rng.below(n as u64) as usize;
+        let to = rng.below(n as u64) as usize;
+        add_edge(&mut g, &mut rng, from, to);
+    }
+
+    g
+}
+
+/// Small, seedable pseudo random number generator, good enough for generating test inputs.
+#[derive(Debug, Clone)]
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `0..bound`. `bound` must not be zero.
+    pub(crate) fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{breadth_first_search, VGraph};
+
+    #[test]
+    fn grid_graph_degrees() {
+        let g = grid_graph(4, 3);
+        assert_eq!(12, g.cells().count());
+        for cell in g.cells() {
+            let degree = g.out_edges(cell).len();
+            assert!((2..=4).contains(&degree));
+        }
+        assert_eq!(2, g.out_edges((0, 0)).len());
+        assert_eq!(4, g.out_edges((1, 1)).len());
+    }
+
+    #[test]
+    fn random_graph_is_well_formed() {
+        let g = random_graph(50, 4, 7);
+        assert_eq!(50, g.num_nodes());
+        assert_eq!(200, g.num_edges());
+        for node in g.nodes() {
+            let out = g.out_edges(node);
+            assert!(!out.is_empty());
+            assert!(!out.contains(&node));
+            let unique: HashSet<_> = out.iter().collect();
+            assert_eq!(out.len(), unique.len());
+            for next in out {
+                assert!((1..=100).contains(&g.dist(node, next)));
+            }
+        }
+    }
+
+    #[test]
+    fn random_graph_is_connected() {
+        let g = random_graph(30, 3, 11);
+        for node in 1..30 {
+            assert!(breadth_first_search(&g, 0, node).is_some());
+            assert!(breadth_first_search(&g, node, 0).is_some());
+        }
+    }
+
+    #[test]
+    fn random_graph_is_deterministic() {
+        assert_eq!(random_graph(20, 3, 5), random_graph(20, 3, 5));
+        assert_ne!(random_graph(20, 3, 5), random_graph(20, 3, 6));
+    }
+
+    #[test]
+    fn random_graph_caps_edges() {
+        assert_eq!(20, random_graph(5, 10, 1).num_edges());
+    }
+}
@@ -0,0 +1,218 @@
+use crate::shortest_dag::shortest_path_dag_ordered;
+use crate::{UndirectedVGraph, VGraph};
+use num::traits::Zero;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// How many shortest paths run through each edge, with paths that tie splitting their share
+/// evenly between them.
+///
+/// Counts the shortest paths from each of `nodes` to every node it reaches, using Brandes'
+/// algorithm over `shortest_path_dag`. Edges on no shortest path are left out. Edges are
+/// directed, so on an undirected graph a path and its reverse both count and each edge appears
+/// once per direction. Edges must cost more than zero.
+///
+/// Edges between communities carry the most paths, which is what Girvan-Newman community
+/// detection looks for.
+pub fn edge_betweenness_centrality<G>(g: G, nodes: &[G::Node]) -> HashMap<(G::Node, G::Node), f64>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let mut scores: HashMap<(G::Node, G::Node), f64> = HashMap::new();
+    for &source in nodes {
+        let (order, preds) = shortest_path_dag_ordered(&g, source);
+
+        // Number of shortest paths from `source` to each node.
+        let mut paths: HashMap<G::Node, f64> = HashMap::new();
+        paths.insert(source, 1.0);
+        for &node in order.iter().skip(1) {
+            let count = preds[&node].iter().map(|pred| paths[pred]).sum();
+            paths.insert(node, count);
+        }
+
+        // Share of the paths to nodes further on that pass through each node.
+        let mut dependency: HashMap<G::Node, f64> = HashMap::new();
+        for &node in order.iter().rev() {
+            let through = 1.0 + dependency.get(&node).copied().unwrap_or(0.0);
+            for &pred in &preds[&node] {
+                let share = paths[&pred] / paths[&node] * through;
+                *scores.entry((pred, node)).or_default() += share;
+                *dependency.entry(pred).or_default() += share;
+            }
+        }
+    }
+    scores
+}
+
+/// Splits `nodes` into `num_communities` groups with the Girvan-Newman algorithm.
+///
+/// Repeatedly removes the edge with the highest `edge_betweenness_centrality`, in both
+/// directions, until the remaining edges connect the nodes into `num_communities` components.
+/// Edges leading outside of `nodes` are ignored. Stops early if every edge is gone, so asking
+/// for more communities than there are nodes gives one per node. Edges that tie for the highest
+/// betweenness are removed in no particular order.
+///
+/// Betweenness is recomputed after every removal, costing a Dijkstra search per node each time,
+/// so this is only practical for small graphs.
+///
+/// Each community lists its nodes in the order of `nodes`, and the communities are ordered by
+/// their first node.
+pub fn girvan_newman<G>(g: G, nodes: &[G::Node], num_communities: usize) -> Vec<Vec<G::Node>>
+where
+    G: UndirectedVGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let mut remaining = RemovedEdges {
+        g,
+        nodes: nodes.iter().copied().collect(),
+        removed: HashSet::new(),
+    };
+    loop {
+        let communities = components(&remaining, nodes);
+        if communities.len() >= num_communities {
+            return communities;
+        }
+        let scores = edge_betweenness_centrality(&remaining, nodes);
+        let Some((&(from, to), _)) = scores.iter().max_by(|(_, a), (_, b)| a.total_cmp(b)) else {
+            return communities;
+        };
+        remaining.removed.insert((from, to));
+        remaining.removed.insert((to, from));
+    }
+}
+
+/// The connected components of `nodes`, in the order described by `girvan_newman`.
+fn components<G>(g: &G, nodes: &[G::Node]) -> Vec<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    let mut community_of: HashMap<G::Node, usize> = HashMap::new();
+    let mut communities: Vec<Vec<G::Node>> = Vec::new();
+    for &root in nodes {
+        if community_of.contains_key(&root) {
+            communities[community_of[&root]].push(root);
+            continue;
+        }
+        let id = communities.len();
+        communities.push(vec![root]);
+        community_of.insert(root, id);
+        let mut to_visit = vec![root];
+        while let Some(cur) = to_visit.pop() {
+            for next in g.out_edges(cur) {
+                if let Entry::Vacant(entry) = community_of.entry(next) {
+                    entry.insert(id);
+                    to_visit.push(next);
+                }
+            }
+        }
+    }
+    communities
+}
+
+/// A graph restricted to `nodes`, without the `removed` edges.
+struct RemovedEdges<G>
+where
+    G: VGraph,
+{
+    g: G,
+    nodes: HashSet<G::Node>,
+    removed: HashSet<(G::Node, G::Node)>,
+}
+
+impl<G> VGraph for RemovedEdges<G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    type Node = G::Node;
+
+    type Dist = G::Dist;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        let mut neighbors = self.g.out_edges(node);
+        neighbors
+            .retain(|&next| self.nodes.contains(&next) && !self.removed.contains(&(node, next)));
+        neighbors
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        self.g.dist(from, to)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.g.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AdjacencyListGraph, SymmetricWrapper};
+
+    #[test]
+    fn bridge_between_triangles_scores_highest() {
+        let mut g = AdjacencyListGraph::new();
+        let both_ways = [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)];
+        for (a, b) in both_ways {
+            g.add_edge(a, b, 1);
+            g.add_edge(b, a, 1);
+        }
+        let nodes: Vec<u32> = (0..6).collect();
+        let scores = edge_betweenness_centrality(&g, &nodes);
+
+        // Each of the 3 nodes on one side has a single shortest path to each of the 3 on the
+        // other, all crossing the bridge.
+        assert_eq!(9.0, scores[&(2, 3)]);
+        assert_eq!(9.0, scores[&(3, 2)]);
+        // 0 to 2 and on to the other side.
+        assert_eq!(4.0, scores[&(0, 2)]);
+        assert_eq!(1.0, scores[&(0, 1)]);
+        let highest = scores.values().copied().fold(0.0, f64::max);
+        assert_eq!(9.0, highest);
+        assert_eq!(14, scores.len());
+    }
+
+    #[test]
+    fn ties_split_paths() {
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(0, 1, 1);
+        g.add_edge(0, 2, 1);
+        g.add_edge(1, 3, 1);
+        g.add_edge(2, 3, 1);
+        g.add_edge(0, 3, 5);
+        let scores = edge_betweenness_centrality(&g, &[0, 1, 2, 3]);
+        assert_eq!(
+            HashMap::from([((0, 1), 1.5), ((0, 2), 1.5), ((1, 3), 1.5), ((2, 3), 1.5)]),
+            scores
+        );
+    }
+
+    #[test]
+    fn splits_two_clusters() {
+        let mut g = AdjacencyListGraph::new();
+        for cluster in [[0, 1, 2, 3], [4, 5, 6, 7]] {
+            for a in cluster {
+                for b in cluster {
+                    if a < b {
+                        g.add_edge(a, b, 1);
+                    }
+                }
+            }
+        }
+        g.add_edge(3, 4, 1);
+        let g = SymmetricWrapper::new(g);
+        let nodes: Vec<u32> = (0..8).collect();
+
+        assert_eq!(vec![nodes.clone()], girvan_newman(&g, &nodes, 1));
+        assert_eq!(
+            vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7]],
+            girvan_newman(&g, &nodes, 2)
+        );
+        assert_eq!(8, girvan_newman(&g, &nodes, 20).len());
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{back_track, ReversibleVGraph};
+use num::traits::Zero;
+use priority_queue::PriorityQueue;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Finds a shortest path from `start` to `end` by searching forward from `start` and backward
+/// from `end` at the same time, stopping once the searches prove their best meeting point is
+/// optimal.
+///
+/// `h_forward` estimates the distance from a node to `end`, `h_backward` the distance from
+/// `start` to a node. Both must be consistent, as for `a_star_search`, and all zero gives
+/// bidirectional Dijkstra.
+///
+/// The search stops when the smallest estimate on either frontier is at least the cost of the
+/// best path found so far. The sum of the two frontiers, as used by bidirectional Dijkstra, is not
+/// a bound once heuristics are involved, because each frontier's estimate already covers the
+/// whole path.
+pub fn bidirectional_a_star<G, H1, H2>(
+    g: G,
+    start: G::Node,
+    end: G::Node,
+    h_forward: H1,
+    h_backward: H2,
+) -> Option<Vec<G::Node>>
+where
+    G: ReversibleVGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    H1: Fn(G::Node) -> G::Dist,
+    H2: Fn(G::Node) -> G::Dist,
+{
+    let mut forward = Side::new(start, h_forward(start));
+    let mut backward = Side::new(end, h_backward(end));
+    // The cheapest complete path found so far, as its cost and the node where the searches met.
+    let mut best: Option<(G::Dist, G::Node)> = if start == end {
+        Some((G::Dist::zero(), start))
+    } else {
+        None
+    };
+
+    // Once one side has settled everything it can reach, every path has been seen.
+    while let (Some(forward_top), Some(backward_top)) = (forward.top(), backward.top()) {
+        if let Some((best_cost, _)) = best {
+            if forward_top >= best_cost || backward_top >= best_cost {
+                break;
+            }
+        }
+
+        if forward_top <= backward_top {
+            let (cur, _) = forward.queue.pop().expect("top was Some.");
+            for next in g.out_edges(cur) {
+                let cost = g.dist(cur, next);
+                forward.relax(cur, next, cost, &h_forward, &backward.dist, &mut best);
+            }
+        } else {
+            let (cur, _) = backward.queue.pop().expect("top was Some.");
+            for next in g.in_edges(cur) {
+                let cost = g.dist(next, cur);
+                backward.relax(cur, next, cost, &h_backward, &forward.dist, &mut best);
+            }
+        }
+    }
+
+    let (_, meet) = best?;
+    let mut path = back_track(&forward.prev, meet);
+    let mut to_end = back_track(&backward.prev, meet);
+    to_end.reverse();
+    path.extend(to_end.into_iter().skip(1));
+    Some(path)
+}
+
+/// The state of the search in one direction.
+struct Side<N, D>
+where
+    N: Hash + Eq,
+    D: Ord,
+{
+    queue: PriorityQueue<N, Reverse<D>>,
+    dist: HashMap<N, D>,
+    // Points each node one step back towards where this side started.
+    prev: HashMap<N, N>,
+}
+
+impl<N, D> Side<N, D>
+where
+    N: Hash + Eq + Copy,
+    D: Zero + Ord + Copy,
+{
+    fn new(origin: N, estimate: D) -> Self {
+        let mut queue = PriorityQueue::new();
+        queue.push(origin, Reverse(estimate));
+        let mut dist = HashMap::new();
+        dist.insert(origin, D::zero());
+        Side {
+            queue,
+            dist,
+            prev: HashMap::new(),
+        }
+    }
+
+    /// The smallest estimated path cost left on this frontier.
+    fn top(&self) -> Option<D> {
+        self.queue.peek().map(|(_, Reverse(estimate))| *estimate)
+    }
+
+    /// Reaches `next` from `cur` by an edge costing `cost`, and records a meeting with the other
+    /// side if it is the cheapest path so far.
+    fn relax<H>(
+        &mut self,
+        cur: N,
+        next: N,
+        cost: D,
+        heuristic: &H,
+        other_dist: &HashMap<N, D>,
+        best: &mut Option<(D, N)>,
+    ) where
+        H: Fn(N) -> D,
+    {
+        let to_next = self.dist[&cur] + cost;
+        if self.dist.get(&next).is_some_and(|&known| to_next >= known) {
+            return;
+        }
+        self.dist.insert(next, to_next);
+        self.prev.insert(next, cur);
+        self.queue.push(next, Reverse(to_next + heuristic(next)));
+
+        if let Some(&rest) = other_dist.get(&next) {
+            let total = to_next + rest;
+            if best.is_none_or(|(best_cost, _)| total < best_cost) {
+                *best = Some((total, next));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::Cycles;
+    use crate::{a_star_search, path_length, AdjacencyListGraph};
+
+    #[test]
+    fn matches_a_star_on_cycles() {
+        for start in 1..=10 {
+            for end in 1..=10 {
+                let expected = a_star_search(Cycles {}, start, |n| n == end, |_| 0);
+                let found = bidirectional_a_star(Cycles {}, start, end, |_| 0, |_| 0);
+                assert_eq!(expected.is_some(), found.is_some(), "{start} to {end}");
+                if let (Some(expected), Some(found)) = (expected, found) {
+                    assert_eq!(start, found[0]);
+                    assert_eq!(end, *found.last().unwrap());
+                    assert_eq!(
+                        path_length(Cycles {}, expected),
+                        path_length(Cycles {}, found),
+                        "{start} to {end}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn with_manhattan_heuristics() {
+        let mut g = AdjacencyListGraph::new();
+        for x in 0..6i32 {
+            for y in 0..6i32 {
+                // A wall down the middle with a gap at the top.
+                let blocked = |x: i32, y: i32| x == 3 && y > 0;
+                if blocked(x, y) {
+                    continue;
+                }
+                for (nx, ny) in [(x + 1, y), (x, y + 1)] {
+                    if nx < 6 && ny < 6 && !blocked(nx, ny) {
+                        g.add_edge((x, y), (nx, ny), 1);
+                        g.add_edge((nx, ny), (x, y), 1);
+                    }
+                }
+            }
+        }
+        let (start, end) = ((0, 5), (5, 5));
+        let manhattan =
+            |(ax, ay): (i32, i32), (bx, by): (i32, i32)| (ax - bx).abs() + (ay - by).abs();
+
+        let path = bidirectional_a_star(
+            &g,
+            start,
+            end,
+            |n| manhattan(n, end),
+            |n| manhattan(start, n),
+        )
+        .unwrap();
+        assert_eq!(15, path_length(&g, path));
+    }
+
+    #[test]
+    fn start_is_end() {
+        assert_eq!(
+            Some(vec![4]),
+            bidirectional_a_star(Cycles {}, 4, 4, |_| 0, |_| 0)
+        );
+    }
+}
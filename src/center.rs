@@ -0,0 +1,135 @@
+use crate::{a_star_search, dijkstra_iter, shortest_distances_dense, VGraph};
+use num::traits::Zero;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// The distance from `node` to the furthest of `nodes`, or `None` if it can not reach all of
+/// them.
+pub fn eccentricity<G>(g: G, node: G::Node, nodes: &[G::Node]) -> Option<G::Dist>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    shortest_distances_dense(g, node, nodes)
+        .into_values()
+        .try_fold(G::Dist::zero(), |furthest, dist| Some(furthest.max(dist?)))
+}
+
+/// The nodes of `nodes` whose furthest distance to the rest of `nodes` is smallest, such as
+/// the best place for a facility serving all of them.
+///
+/// Only nodes that can reach every other node are candidates, so the result is empty when none
+/// can. Ties are all returned, in the order of `nodes`. Runs a Dijkstra search from every node.
+pub fn graph_center<G>(g: G, nodes: &[G::Node]) -> Vec<G::Node>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let eccentricities: Vec<(G::Node, G::Dist)> = nodes
+        .iter()
+        .filter_map(|&node| Some((node, eccentricity(&g, node, nodes)?)))
+        .collect();
+    let Some(smallest) = eccentricities.iter().map(|&(_, e)| e).min() else {
+        return Vec::new();
+    };
+    eccentricities
+        .into_iter()
+        .filter(|&(_, e)| e == smallest)
+        .map(|(node, _)| node)
+        .collect()
+}
+
+/// A shortest path between the two of `nodes` furthest apart, the path whose length is the
+/// graph's diameter.
+///
+/// Pairs with no path between them are skipped rather than making the diameter infinite. Ties
+/// go to the pair found first, in the order of `nodes`. Returns `None` only when `nodes` is
+/// empty, a single node is a path of length zero. Runs a Dijkstra search from every node.
+pub fn diameter_path<G>(g: G, nodes: &[G::Node]) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let targets: HashSet<G::Node> = nodes.iter().copied().collect();
+    let mut furthest: Option<(G::Dist, G::Node, G::Node)> = None;
+    for &from in nodes {
+        let mut remaining = targets.clone();
+        let mut settle = dijkstra_iter(&g, from);
+        while !remaining.is_empty() {
+            let Some((to, dist)) = settle.next() else {
+                break;
+            };
+            if remaining.remove(&to) && furthest.is_none_or(|(longest, _, _)| dist > longest) {
+                furthest = Some((dist, from, to));
+            }
+        }
+    }
+    let (_, from, to) = furthest?;
+    a_star_search(&g, from, |n| n == to, |_| G::Dist::zero())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::Cycles;
+    use crate::FnGraph;
+
+    /// Nodes `0..len` in a line, joined both ways.
+    fn line(len: u32) -> impl VGraph<Node = u32, Dist = u32> {
+        FnGraph::new(
+            move |n: u32| {
+                [n.checked_sub(1), Some(n + 1).filter(|&m| m < len)]
+                    .into_iter()
+                    .flatten()
+                    .collect()
+            },
+            |_, _| 1,
+        )
+    }
+
+    #[test]
+    fn middle_of_a_line() {
+        let nodes: Vec<u32> = (0..5).collect();
+        assert_eq!(vec![2], graph_center(line(5), &nodes));
+        assert_eq!(Some(2), eccentricity(line(5), 2, &nodes));
+        assert_eq!(Some(4), eccentricity(line(5), 0, &nodes));
+
+        let nodes: Vec<u32> = (0..4).collect();
+        assert_eq!(vec![1, 2], graph_center(line(4), &nodes));
+        assert_eq!(vec![3], graph_center(line(4), &[3]));
+    }
+
+    #[test]
+    fn only_nodes_reaching_everything() {
+        let nodes: Vec<usize> = (1..=10).collect();
+        // Nothing leads to 7, so it is the only node that reaches them all.
+        assert_eq!(None, eccentricity(Cycles {}, 1, &nodes));
+        assert_eq!(vec![7], graph_center(Cycles {}, &nodes));
+        assert_eq!(vec![7], graph_center(Cycles {}, &[7, 8, 1]));
+        let no_edges = FnGraph::new(|_: u32| vec![], |_, _| 1u32);
+        assert!(graph_center(no_edges, &[0, 1]).is_empty());
+        assert!(graph_center(line(3), &[]).is_empty());
+    }
+
+    #[test]
+    fn diameter_of_a_line_is_end_to_end() {
+        let nodes: Vec<u32> = (0..5).collect();
+        assert_eq!(Some(vec![0, 1, 2, 3, 4]), diameter_path(line(5), &nodes));
+        assert_eq!(Some(vec![3, 2, 1]), diameter_path(line(5), &[3, 2, 1]));
+        assert_eq!(Some(vec![2]), diameter_path(line(5), &[2]));
+        assert_eq!(None, diameter_path(line(5), &[]));
+    }
+
+    #[test]
+    fn diameter_skips_unreachable_pairs() {
+        let nodes: Vec<usize> = (1..=10).collect();
+        // 7 can not be reached from the others, but 5 is 8 away from it.
+        assert_eq!(
+            Some(vec![7, 8, 9, 10, 1, 3, 5]),
+            diameter_path(Cycles {}, &nodes)
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use crate::VGraph;
+use num::traits::Zero;
+use priority_queue::PriorityQueue;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// `a_star_search` for graphs whose nodes are not `Copy`, such as board states held in a
+/// `Vec` or `String`.
+///
+/// Each node found is stored once and the search works on indices into that store, so a node
+/// is only cloned to hand it to `out_edges`, `dist` and the store. `is_end` and `heuristic`
+/// borrow the node instead of taking it. The found path is moved out of the store, without
+/// cloning.
+pub fn a_star_search_cloned<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Clone,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(&G::Node) -> bool,
+    H: Fn(&G::Node) -> G::Dist,
+{
+    let mut to_explore = PriorityQueue::new();
+    to_explore.push_increase(0, Reverse(heuristic(&start)));
+    // Every node found so far, and its index in `nodes`, `dist_from_start` and `prev`.
+    let mut ids: HashMap<G::Node, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut nodes = vec![start];
+    let mut dist_from_start = vec![G::Dist::zero()];
+    let mut prev: Vec<Option<usize>> = vec![None];
+    while let Some((cur, _priority)) = to_explore.pop() {
+        if is_end(&nodes[cur]) {
+            return Some(take_path(nodes, &prev, cur));
+        }
+
+        let cur_node = nodes[cur].clone();
+        for next in g.out_edges(cur_node.clone()) {
+            let start_to_next = dist_from_start[cur] + g.dist(cur_node.clone(), next.clone());
+            let next_id = match ids.get(&next) {
+                // We already have a path to next that is at least as good, skip this one.
+                Some(&id) if start_to_next >= dist_from_start[id] => continue,
+                Some(&id) => {
+                    dist_from_start[id] = start_to_next;
+                    prev[id] = Some(cur);
+                    id
+                }
+                None => {
+                    let id = nodes.len();
+                    nodes.push(next.clone());
+                    dist_from_start.push(start_to_next);
+                    prev.push(Some(cur));
+                    ids.insert(next, id);
+                    id
+                }
+            };
+            let h_dist = start_to_next + heuristic(&nodes[next_id]);
+            to_explore.push_increase(next_id, Reverse(h_dist));
+        }
+    }
+
+    // Ran out of places to explore, end not found.
+    None
+}
+
+/// Moves the path ending at `end` out of `nodes`, following `prev` back to the start.
+fn take_path<N>(nodes: Vec<N>, prev: &[Option<usize>], end: usize) -> Vec<N> {
+    let mut ids = vec![end];
+    while let Some(id) = prev[*ids.last().unwrap()] {
+        ids.push(id);
+    }
+    let mut nodes: Vec<Option<N>> = nodes.into_iter().map(Some).collect();
+    ids.into_iter()
+        .rev()
+        .map(|id| nodes[id].take().expect("A path never visits a node twice."))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::Cycles;
+    use crate::{a_star_search, FnGraph};
+
+    /// Orderings of bytes, where each edge swaps two neighbors.
+    fn swaps() -> impl VGraph<Node = Vec<u8>, Dist = u32> {
+        FnGraph::new(
+            |order: Vec<u8>| {
+                (1..order.len())
+                    .map(|i| {
+                        let mut next = order.clone();
+                        next.swap(i - 1, i);
+                        next
+                    })
+                    .collect()
+            },
+            |_, _| 1,
+        )
+    }
+
+    #[test]
+    fn sorts_by_swapping_neighbors() {
+        let path = a_star_search_cloned(swaps(), vec![3, 1, 2], |n| n.is_sorted(), |_| 0).unwrap();
+        // One swap per pair out of order.
+        assert_eq!(3, path.len());
+        assert_eq!(vec![3, 1, 2], path[0]);
+        assert_eq!(vec![1, 2, 3], path[2]);
+
+        let path = a_star_search_cloned(swaps(), vec![4, 3, 2, 1], |n| n.is_sorted(), |_| 0);
+        assert_eq!(7, path.unwrap().len());
+        assert_eq!(
+            Some(vec![vec![1, 2]]),
+            a_star_search_cloned(swaps(), vec![1, 2], |n| n.is_sorted(), |_| 0)
+        );
+        assert_eq!(
+            None,
+            a_star_search_cloned(swaps(), vec![1, 2], |n| n.len() == 3, |_| 0)
+        );
+    }
+
+    #[test]
+    fn matches_a_star_search() {
+        for end in 1..=10 {
+            assert_eq!(
+                a_star_search(Cycles {}, 1, |n| n == end, |_| 0),
+                a_star_search_cloned(Cycles {}, 1, |&n| n == end, |_| 0)
+            );
+        }
+    }
+}
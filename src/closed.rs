@@ -0,0 +1,146 @@
+use std::collections::HashSet;
+use std::hash::{BuildHasher, Hash};
+
+/// The set of nodes a traversal has already reached, see `breadth_first_search_with`,
+/// `explore_with` and `Bfs::with_closed`.
+///
+/// An implementation may forget nodes to save memory, which only makes the traversal reach
+/// them again. The path found is the same, but the repeated work can be large, and on a cyclic
+/// graph a search for an end it can not reach may never finish. It must never claim to hold a
+/// node that was not inserted, or that node is skipped.
+pub trait ClosedSet<N> {
+    /// Adds `node`, returning true if it was not already in the set.
+    fn insert(&mut self, node: N) -> bool;
+
+    fn contains(&self, node: &N) -> bool;
+}
+
+impl<N, S> ClosedSet<N> for HashSet<N, S>
+where
+    N: Hash + Eq,
+    S: BuildHasher,
+{
+    fn insert(&mut self, node: N) -> bool {
+        HashSet::insert(self, node)
+    }
+
+    fn contains(&self, node: &N) -> bool {
+        HashSet::contains(self, node)
+    }
+}
+
+/// A closed set of `usize` nodes stored as one bit per node, for dense graphs numbered from
+/// zero. Grows to fit the largest node inserted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitSetClosed {
+    words: Vec<u64>,
+}
+
+impl BitSetClosed {
+    pub fn new() -> Self {
+        BitSetClosed { words: Vec::new() }
+    }
+
+    /// A set with room for the nodes `0..num_nodes` without growing.
+    pub fn with_capacity(num_nodes: usize) -> Self {
+        BitSetClosed {
+            words: vec![0; num_nodes.div_ceil(64)],
+        }
+    }
+}
+
+impl ClosedSet<usize> for BitSetClosed {
+    fn insert(&mut self, node: usize) -> bool {
+        let (word, bit) = (node / 64, 1 << (node % 64));
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let new = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        new
+    }
+
+    fn contains(&self, node: &usize) -> bool {
+        self.words
+            .get(node / 64)
+            .is_some_and(|word| word & (1 << (node % 64)) != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::random_graph;
+    use crate::test_graphs::Cycles;
+    use crate::{breadth_first_search, breadth_first_search_with, explore, explore_with};
+    use std::ops::ControlFlow;
+
+    #[test]
+    fn bitset_insert_and_contains() {
+        let mut set = BitSetClosed::new();
+        assert!(set.insert(3));
+        assert!(set.insert(130));
+        assert!(!set.insert(3));
+        assert!(set.contains(&130));
+        assert!(!set.contains(&64));
+        assert!(!set.contains(&1000));
+    }
+
+    /// Remembers nothing, so every edge is followed each time its node is reached.
+    struct Forgetful;
+
+    impl ClosedSet<usize> for Forgetful {
+        fn insert(&mut self, _node: usize) -> bool {
+            true
+        }
+
+        fn contains(&self, _node: &usize) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn forgetful_set_finds_the_same_paths() {
+        // Every node reachable from 1, a search for any other would never finish. 1 is reached
+        // again from 5 and 10.
+        for end in [1, 2, 3, 4, 5, 6, 10] {
+            assert_eq!(
+                breadth_first_search(Cycles {}, 1, end),
+                breadth_first_search_with(Cycles {}, 1, end, &mut Forgetful)
+            );
+        }
+    }
+
+    #[test]
+    fn bitset_matches_hash_set_search() {
+        for end in 1..=10 {
+            assert_eq!(
+                breadth_first_search(Cycles {}, 1, end),
+                breadth_first_search_with(Cycles {}, 1, end, &mut BitSetClosed::new())
+            );
+        }
+
+        let g = random_graph(200, 3, 11);
+        for end in [0, 57, 199] {
+            assert_eq!(
+                breadth_first_search(&g, 5, end),
+                breadth_first_search_with(&g, 5, end, &mut BitSetClosed::with_capacity(200))
+            );
+        }
+    }
+
+    #[test]
+    fn explore_with_bitset_visits_the_same_nodes() {
+        let visit = |order: &mut Vec<(usize, Option<usize>)>, cur, from| {
+            order.push((cur, from));
+            ControlFlow::<()>::Continue(())
+        };
+        let mut hashed = Vec::new();
+        explore(Cycles {}, 7, &mut hashed, visit);
+        let mut bits = Vec::new();
+        let mut closed = BitSetClosed::new();
+        explore_with(Cycles {}, 7, &mut bits, &mut closed, visit);
+        assert_eq!(hashed, bits);
+        assert!((1..=10).all(|node| closed.contains(&node)));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::VGraph;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// Colors `nodes` so that no edge joins two nodes of the same color, using the smallest color
+/// free at each node in the given order.
+///
+/// An edge in either direction is a conflict, so directed graphs need no wrapping. Edges leading
+/// outside of `nodes` are ignored. Colors are numbered from 0, and greedy coloring uses at most
+/// one more color than the largest number of conflicts at any node, but not necessarily the
+/// fewest possible. The order of `nodes` decides the result.
+pub fn greedy_coloring<G>(g: G, nodes: &[G::Node]) -> HashMap<G::Node, usize>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    let mut conflicts: HashMap<G::Node, HashSet<G::Node>> =
+        nodes.iter().map(|&n| (n, HashSet::new())).collect();
+    for &node in nodes {
+        for next in g.out_edges(node) {
+            if next != node && conflicts.contains_key(&next) {
+                conflicts.get_mut(&node).unwrap().insert(next);
+                conflicts.get_mut(&next).unwrap().insert(node);
+            }
+        }
+    }
+
+    let mut colors: HashMap<G::Node, usize> = HashMap::new();
+    for &node in nodes {
+        if colors.contains_key(&node) {
+            continue;
+        }
+        let taken: HashSet<usize> = conflicts[&node]
+            .iter()
+            .filter_map(|other| colors.get(other).copied())
+            .collect();
+        let color = (0..)
+            .find(|c| !taken.contains(c))
+            .expect("There is always a free color.");
+        colors.insert(node, color);
+    }
+    colors
+}
+
+/// The two sides of a bipartite graph.
+type Parts<N> = (Vec<N>, Vec<N>);
+
+/// Splits `nodes` into two parts with every edge running between them, or `None` if the graph
+/// has an odd cycle and no such split exists.
+///
+/// Edges are treated as undirected and those leading outside of `nodes` are ignored. Each
+/// connected piece is 2-colored with a breadth first search, starting from its first node in
+/// `nodes`, which goes in the first part. A node with an edge to itself makes the graph not
+/// bipartite. Both parts keep the order of `nodes`.
+pub fn is_bipartite<G>(g: G, nodes: &[G::Node]) -> Option<Parts<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    let mut neighbors: HashMap<G::Node, Vec<G::Node>> =
+        nodes.iter().map(|&n| (n, Vec::new())).collect();
+    for &node in nodes {
+        for next in g.out_edges(node) {
+            if neighbors.contains_key(&next) {
+                neighbors.get_mut(&node).unwrap().push(next);
+                neighbors.get_mut(&next).unwrap().push(node);
+            }
+        }
+    }
+
+    let mut side: HashMap<G::Node, bool> = HashMap::new();
+    for &root in nodes {
+        if side.contains_key(&root) {
+            continue;
+        }
+        side.insert(root, false);
+        let mut to_explore = VecDeque::from([root]);
+        while let Some(cur) = to_explore.pop_front() {
+            let cur_side = side[&cur];
+            for &next in &neighbors[&cur] {
+                match side.get(&next) {
+                    Some(&next_side) if next_side == cur_side => return None,
+                    Some(_) => {}
+                    None => {
+                        side.insert(next, !cur_side);
+                        to_explore.push_back(next);
+                    }
+                }
+            }
+        }
+    }
+    Some(nodes.iter().partition(|node| !side[*node]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::Cycles;
+    use crate::AdjacencyListGraph;
+
+    fn assert_proper<G>(g: G, nodes: &[G::Node], colors: &HashMap<G::Node, usize>)
+    where
+        G: VGraph,
+        G::Node: Hash + Eq + Copy + std::fmt::Debug,
+    {
+        for &node in nodes {
+            for next in g.out_edges(node) {
+                if next != node && colors.contains_key(&next) {
+                    assert_ne!(colors[&node], colors[&next], "{node:?} and {next:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn conflicting_tasks_get_different_colors() {
+        // A triangle of conflicts, with one more task touching only one corner.
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge("a", "b", ());
+        g.add_edge("b", "c", ());
+        g.add_edge("c", "a", ());
+        g.add_edge("d", "a", ());
+        let nodes = ["a", "b", "c", "d"];
+        let colors = greedy_coloring(&g, &nodes);
+        assert_proper(&g, &nodes, &colors);
+        assert_eq!(3, colors.values().collect::<HashSet<_>>().len());
+        assert_eq!(1, colors["d"]);
+    }
+
+    #[test]
+    fn colors_cycles() {
+        let nodes: Vec<usize> = (1..=10).collect();
+        let colors = greedy_coloring(Cycles {}, &nodes);
+        assert_eq!(10, colors.len());
+        assert_proper(Cycles {}, &nodes, &colors);
+    }
+
+    #[test]
+    fn splits_bipartite_graphs() {
+        // Two rows of a ladder, with rungs and rails pointing every which way.
+        let mut g = AdjacencyListGraph::new();
+        for (from, to) in [
+            (0, 1),
+            (2, 1),
+            (2, 3),
+            (4, 0),
+            (1, 5),
+            (6, 2),
+            (7, 3),
+            (6, 5),
+            (6, 7),
+        ] {
+            g.add_edge(from, to, ());
+        }
+        g.add_node(8);
+        let nodes: Vec<usize> = (0..=8).collect();
+        assert_eq!(
+            Some((vec![0, 2, 5, 7, 8], vec![1, 3, 4, 6])),
+            is_bipartite(&g, &nodes)
+        );
+        // Without 0 the pieces come apart, and 4 starts its own.
+        assert_eq!(
+            Some((vec![1, 3, 4, 6], vec![2, 5, 7])),
+            is_bipartite(&g, &nodes[1..8])
+        );
+
+        g.add_edge(8, 8, ());
+        assert_eq!(None, is_bipartite(&g, &nodes));
+        assert_eq!(Some((vec![], vec![])), is_bipartite(&g, &[]));
+    }
+
+    #[test]
+    fn cycles_has_odd_cycles() {
+        let nodes: Vec<usize> = (1..=10).collect();
+        // 1 2 3 is a triangle once direction is ignored.
+        assert_eq!(None, is_bipartite(Cycles {}, &nodes));
+        // The other cycle, 1 3 4 10 around to 1, is even without 2 and 5.
+        assert_eq!(
+            Some((vec![1, 4, 6], vec![3, 10])),
+            is_bipartite(Cycles {}, &[1, 3, 4, 6, 10])
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use crate::VGraph;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// Wraps a graph and panics if `out_edges` gives a different set of neighbors for a node it has
+/// answered before.
+///
+/// Searches assume a node always has the same neighbors, a graph that breaks this gives
+/// inconsistent results that are hard to track down. The check only runs with debug assertions
+/// enabled, in release builds the wrapper just forwards to the graph.
+pub struct ConsistencyChecker<G>
+where
+    G: VGraph,
+{
+    g: G,
+    seen: RefCell<HashMap<G::Node, HashSet<G::Node>>>,
+}
+
+impl<G> ConsistencyChecker<G>
+where
+    G: VGraph,
+{
+    pub fn new(g: G) -> Self {
+        ConsistencyChecker {
+            g,
+            seen: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl<G> VGraph for ConsistencyChecker<G>
+where
+    G: VGraph,
+    G::Node: Eq + Hash + Copy + Debug,
+{
+    type Node = G::Node;
+
+    type Dist = G::Dist;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        let neighbors = self.g.out_edges(node);
+        if cfg!(debug_assertions) {
+            let current: HashSet<G::Node> = neighbors.iter().copied().collect();
+            let mut seen = self.seen.borrow_mut();
+            let first = seen.entry(node).or_insert_with(|| current.clone());
+            assert_eq!(*first, current, "out_edges({node:?}) changed between calls");
+        }
+        neighbors
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        self.g.dist(from, to)
+    }
+
+    fn is_terminal(&self, node: Self::Node) -> bool {
+        self.g.is_terminal(node)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.g.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::Cycles;
+    use crate::{a_star_search, breadth_first_search};
+    use std::cell::Cell;
+
+    /// Gains an extra neighbor every time it is asked.
+    struct Growing {
+        calls: Cell<usize>,
+    }
+
+    impl VGraph for Growing {
+        type Node = usize;
+
+        type Dist = usize;
+
+        fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+            self.calls.set(self.calls.get() + 1);
+            (node + 1..=node + self.calls.get()).collect()
+        }
+
+        fn dist(&self, _from: Self::Node, _to: Self::Node) -> Self::Dist {
+            1
+        }
+    }
+
+    #[test]
+    fn consistent_graph_passes() {
+        let checked = ConsistencyChecker::new(Cycles {});
+        assert_eq!(
+            Some(vec![1, 3, 4, 10]),
+            breadth_first_search(&checked, 1, 10)
+        );
+        assert_eq!(
+            Some(vec![1, 3, 4, 10]),
+            a_star_search(&checked, 1, |n| n == 10, |_| 0)
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "out_edges(1) changed between calls")]
+    fn inconsistent_graph_panics() {
+        let checked = ConsistencyChecker::new(Growing {
+            calls: Cell::new(0),
+        });
+        checked.out_edges(1);
+        checked.out_edges(1);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::{back_track, AStarBuffers, Explored, VGraph};
+use num::traits::Zero;
+use priority_queue::PriorityQueue;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Buffers for running many searches on one graph without allocating for each of them.
+///
+/// Each search clears the buffers before starting but keeps their capacity, so after the first
+/// few queries a search only allocates the path it returns.
+///
+/// A search can also be run in steps with `begin` and `resume`. With the `serde` feature the
+/// context can be saved between steps, and a search continued later from the saved state.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "G::Node: Serialize, G::Dist: Serialize",
+        deserialize = "G::Node: Deserialize<'de>, G::Dist: Deserialize<'de>"
+    ))
+)]
+pub struct SearchContext<G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq,
+    G::Dist: Ord,
+{
+    prev: HashMap<G::Node, G::Node>,
+    dist_from_start: HashMap<G::Node, G::Dist>,
+    to_explore: PriorityQueue<G::Node, Reverse<G::Dist>>,
+}
+
+impl<G> SearchContext<G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    pub fn new() -> Self {
+        SearchContext {
+            prev: HashMap::new(),
+            dist_from_start: HashMap::new(),
+            to_explore: PriorityQueue::new(),
+        }
+    }
+
+    /// Empties the buffers, keeping their allocated capacity.
+    pub fn clear(&mut self) {
+        self.prev.clear();
+        self.dist_from_start.clear();
+        self.to_explore.clear();
+    }
+
+    /// Same as `a_star_search`, using this context's buffers.
+    pub fn a_star_search<F, H>(
+        &mut self,
+        g: &G,
+        start: G::Node,
+        is_end: F,
+        heuristic: H,
+    ) -> Option<Vec<G::Node>>
+    where
+        F: Fn(G::Node) -> bool,
+        H: Fn(G::Node) -> G::Dist,
+    {
+        self.begin(start, &heuristic);
+        match self.resume(g, is_end, heuristic, usize::MAX) {
+            SearchStatus::Found(path) => Some(path),
+            SearchStatus::Exhausted => None,
+            SearchStatus::Paused => unreachable!("The search has no step limit."),
+        }
+    }
+
+    /// Clears the context and sets it up for an A* search from `start`, to be run with
+    /// `resume`.
+    pub fn begin<H>(&mut self, start: G::Node, heuristic: H)
+    where
+        H: Fn(G::Node) -> G::Dist,
+    {
+        self.clear();
+        self.to_explore.push(start, Reverse(heuristic(start)));
+        self.dist_from_start.insert(start, G::Dist::zero());
+    }
+
+    /// Continues the search set up by `begin`, exploring at most `max_steps` nodes before
+    /// pausing. `is_end` and `heuristic` should be the same on every call.
+    pub fn resume<F, H>(
+        &mut self,
+        g: &G,
+        is_end: F,
+        heuristic: H,
+        max_steps: usize,
+    ) -> SearchStatus<G::Node>
+    where
+        F: Fn(G::Node) -> bool,
+        H: Fn(G::Node) -> G::Dist,
+    {
+        let search = AStarBuffers {
+            prev: &mut self.prev,
+            dist_from_start: &mut self.dist_from_start,
+            to_explore: &mut self.to_explore,
+        };
+        let explored = search.run(
+            g,
+            |_, n| is_end(n),
+            heuristic,
+            |from_start, h, _| Reverse(from_start + h),
+            max_steps,
+        );
+        match explored {
+            Explored::Goal(end) => SearchStatus::Found(back_track(&self.prev, end)),
+            Explored::Exhausted => SearchStatus::Exhausted,
+            Explored::Paused => SearchStatus::Paused,
+        }
+    }
+}
+
+/// Where a search run with `SearchContext::resume` stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchStatus<N> {
+    Found(Vec<N>),
+    /// Every reachable node was explored without finding a goal.
+    Exhausted,
+    /// The step limit was reached, `resume` again to continue.
+    Paused,
+}
+
+impl<G> Default for SearchContext<G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::random_graph;
+    use crate::test_graphs::{Cycles, Ex};
+    use crate::{a_star_search, path_length};
+
+    #[test]
+    fn matches_fresh_searches_on_cycles() {
+        let mut context = SearchContext::new();
+        for start in 1..=10 {
+            for end in 1..=10 {
+                assert_eq!(
+                    a_star_search(Cycles {}, start, |n| n == end, |_| 0),
+                    context.a_star_search(&Cycles {}, start, |n| n == end, |_| 0),
+                    "{start} to {end}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn many_queries_on_one_context() {
+        let g = random_graph(60, 3, 4);
+        let mut context = SearchContext::new();
+        for i in 0..100 {
+            let (start, end) = ((i * 7) % 60, (i * 13 + 5) % 60);
+            let fresh = a_star_search(&g, start, |n| n == end, |_| 0).unwrap();
+            let reused = context
+                .a_star_search(&g, start, |n| n == end, |_| 0)
+                .unwrap();
+            assert_eq!(path_length(&g, fresh), path_length(&g, reused));
+        }
+    }
+
+    #[test]
+    fn resume_in_steps() {
+        let mut context = SearchContext::new();
+        context.begin(1, |_| 0);
+        assert_eq!(
+            SearchStatus::Paused,
+            context.resume(&Cycles {}, |n| n == 10, |_| 0, 2)
+        );
+        assert_eq!(
+            SearchStatus::Found(vec![1, 3, 4, 10]),
+            context.resume(&Cycles {}, |n| n == 10, |_| 0, 100)
+        );
+
+        let mut context = SearchContext::new();
+        context.begin(3, |_| 0);
+        assert_eq!(
+            SearchStatus::Exhausted,
+            context.resume(&Ex::new(), |n| n == 1, |_| 0, 100)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn save_and_resume() {
+        let g = random_graph(60, 3, 4);
+        let expected = a_star_search(&g, 0, |n| n == 42, |_| 0).unwrap();
+
+        let mut context = SearchContext::new();
+        context.begin(0, |_| 0);
+        assert_eq!(
+            SearchStatus::Paused,
+            context.resume(&g, |n| n == 42, |_| 0, 10)
+        );
+        let saved = serde_json::to_string(&context).unwrap();
+
+        let mut restored: SearchContext<_> = serde_json::from_str(&saved).unwrap();
+        let found = match restored.resume(&g, |n| n == 42, |_| 0, usize::MAX) {
+            SearchStatus::Found(path) => path,
+            status => panic!("expected a path, got {status:?}"),
+        };
+        assert_eq!(path_length(&g, expected), path_length(&g, found));
+    }
+}
@@ -0,0 +1,329 @@
+use crate::ReversibleVGraph;
+use num::traits::Zero;
+use priority_queue::PriorityQueue;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Edges of the graph left to contract, keyed by one end and then the other.
+type Remaining<N, D> = HashMap<N, HashMap<N, D>>;
+
+/// A graph preprocessed so that repeated shortest path queries only explore a small part of it.
+///
+/// Nodes are contracted one at a time, least important first. Contracting a node removes it and
+/// adds a shortcut edge between its neighbors wherever the shortest path between them went
+/// through it. A query then searches forward from the start and backward from the end, only
+/// ever moving to nodes contracted later, and the two searches meet at the most important node
+/// on the path. Shortcuts are expanded back into the edges they replaced before returning.
+///
+/// Building costs a local Dijkstra search per neighbor of every contracted node, so it is only
+/// worth it when many queries are answered on the same graph.
+#[derive(Debug, Clone)]
+pub struct ContractionHierarchy<N, D>
+where
+    N: Eq + Hash,
+{
+    // The order nodes were contracted in.
+    rank: HashMap<N, usize>,
+    // Edges to nodes contracted later.
+    up: HashMap<N, Vec<(N, D)>>,
+    // Edges from nodes contracted later, keyed by their target.
+    down: HashMap<N, Vec<(N, D)>>,
+    // The node each shortcut skips over.
+    via: HashMap<(N, N), N>,
+}
+
+impl<N, D> ContractionHierarchy<N, D>
+where
+    N: Hash + Eq + Copy,
+    D: Zero + Ord + Copy,
+{
+    /// Contracts every node in `nodes`, reading edges into them with `in_edges`.
+    ///
+    /// Edges leading to or from nodes outside of `nodes` are ignored, and so are self loops.
+    /// When several edges join the same pair of nodes the cheapest is kept.
+    pub fn build<G>(g: G, nodes: &[N]) -> Self
+    where
+        G: ReversibleVGraph<Node = N, Dist = D>,
+    {
+        let included: HashSet<N> = nodes.iter().copied().collect();
+        let mut out: Remaining<N, D> = nodes.iter().map(|&n| (n, HashMap::new())).collect();
+        let mut inc: Remaining<N, D> = nodes.iter().map(|&n| (n, HashMap::new())).collect();
+        for &to in nodes {
+            for from in g.in_edges(to) {
+                if from != to && included.contains(&from) {
+                    add_if_shorter(&mut out, &mut inc, from, to, g.dist(from, to));
+                }
+            }
+        }
+
+        // Neighbors already contracted, which spreads contraction evenly over the graph.
+        let mut contracted_neighbors: HashMap<N, isize> = HashMap::new();
+        let importance = |out: &Remaining<N, D>, inc: &Remaining<N, D>, shortcuts: usize, n| {
+            let removed = out[&n].len() + inc[&n].len();
+            shortcuts as isize - removed as isize
+        };
+
+        let mut queue = PriorityQueue::new();
+        for &n in nodes {
+            let shortcuts = shortcuts_for(&out, &inc, n).len();
+            queue.push(n, Reverse(importance(&out, &inc, shortcuts, n)));
+        }
+
+        let mut hierarchy = ContractionHierarchy {
+            rank: HashMap::new(),
+            up: HashMap::new(),
+            down: HashMap::new(),
+            via: HashMap::new(),
+        };
+        while let Some((cur, _)) = queue.pop() {
+            // Contracting other nodes changes a node's importance, so it is recomputed here and
+            // the node put back if it is no longer the least important.
+            let shortcuts = shortcuts_for(&out, &inc, cur);
+            let cur_importance = importance(&out, &inc, shortcuts.len(), cur)
+                + contracted_neighbors.get(&cur).copied().unwrap_or(0);
+            if let Some((_, &Reverse(next_importance))) = queue.peek() {
+                if cur_importance > next_importance {
+                    queue.push(cur, Reverse(cur_importance));
+                    continue;
+                }
+            }
+
+            hierarchy.rank.insert(cur, hierarchy.rank.len());
+            let cur_out = out.remove(&cur).expect("Uncontracted nodes have edges.");
+            let cur_in = inc.remove(&cur).expect("Uncontracted nodes have edges.");
+            for &next in cur_out.keys() {
+                inc.get_mut(&next)
+                    .expect("Neighbors are uncontracted.")
+                    .remove(&cur);
+                *contracted_neighbors.entry(next).or_default() += 1;
+            }
+            for &prev in cur_in.keys() {
+                out.get_mut(&prev)
+                    .expect("Neighbors are uncontracted.")
+                    .remove(&cur);
+                *contracted_neighbors.entry(prev).or_default() += 1;
+            }
+            hierarchy.up.insert(cur, cur_out.into_iter().collect());
+            hierarchy.down.insert(cur, cur_in.into_iter().collect());
+
+            for (from, to, dist) in shortcuts {
+                if add_if_shorter(&mut out, &mut inc, from, to, dist) {
+                    hierarchy.via.insert((from, to), cur);
+                }
+            }
+        }
+
+        hierarchy
+    }
+
+    /// A shortest path from `start` to `end` in the original graph, or `None` if there is none
+    /// or either node was not given to `build`.
+    pub fn query(&self, start: N, end: N) -> Option<Vec<N>> {
+        if !self.rank.contains_key(&start) || !self.rank.contains_key(&end) {
+            return None;
+        }
+        let (forward_dist, forward_prev) = upward_search(&self.up, start);
+        let (backward_dist, backward_prev) = upward_search(&self.down, end);
+        let (_, meet) = forward_dist
+            .iter()
+            .filter_map(|(node, &to_node)| {
+                backward_dist
+                    .get(node)
+                    .map(|&from_node| (to_node + from_node, *node))
+            })
+            .min_by_key(|&(cost, node)| (cost, self.rank[&node]))?;
+
+        let mut hierarchy_path = vec![meet];
+        let mut cur = meet;
+        while let Some(&prev) = forward_prev.get(&cur) {
+            hierarchy_path.push(prev);
+            cur = prev;
+        }
+        hierarchy_path.reverse();
+        cur = meet;
+        while let Some(&next) = backward_prev.get(&cur) {
+            hierarchy_path.push(next);
+            cur = next;
+        }
+
+        let mut path = vec![start];
+        for step in hierarchy_path.windows(2) {
+            self.unpack(step[0], step[1], &mut path);
+        }
+        Some(path)
+    }
+
+    /// Pushes the nodes after `from` on the original edges that `from` to `to` stands for.
+    fn unpack(&self, from: N, to: N, path: &mut Vec<N>) {
+        // Edges still to expand, the last one comes next along the path.
+        let mut pending = vec![(from, to)];
+        while let Some((from, to)) = pending.pop() {
+            match self.via.get(&(from, to)) {
+                Some(&middle) => {
+                    pending.push((middle, to));
+                    pending.push((from, middle));
+                }
+                None => path.push(to),
+            }
+        }
+    }
+}
+
+/// Adds the edge unless an edge at least as short already joins the nodes. Returns whether it
+/// was added.
+fn add_if_shorter<N, D>(
+    out: &mut Remaining<N, D>,
+    inc: &mut Remaining<N, D>,
+    from: N,
+    to: N,
+    dist: D,
+) -> bool
+where
+    N: Hash + Eq + Copy,
+    D: Ord + Copy,
+{
+    let from_out = out.get_mut(&from).expect("Edges join remaining nodes.");
+    if from_out.get(&to).is_some_and(|&best| best <= dist) {
+        return false;
+    }
+    from_out.insert(to, dist);
+    inc.get_mut(&to)
+        .expect("Edges join remaining nodes.")
+        .insert(from, dist);
+    true
+}
+
+/// The shortcuts contracting `node` needs, for pairs of its neighbors with no path as short
+/// around it.
+fn shortcuts_for<N, D>(out: &Remaining<N, D>, inc: &Remaining<N, D>, node: N) -> Vec<(N, N, D)>
+where
+    N: Hash + Eq + Copy,
+    D: Zero + Ord + Copy,
+{
+    let mut shortcuts = Vec::new();
+    for (&from, &to_node) in &inc[&node] {
+        let through: Vec<(N, D)> = out[&node]
+            .iter()
+            .filter(|&(&to, _)| to != from)
+            .map(|(&to, &from_node)| (to, to_node + from_node))
+            .collect();
+        let Some(limit) = through.iter().map(|&(_, dist)| dist).max() else {
+            continue;
+        };
+        let witnesses = witness_search(out, from, node, limit);
+        for (to, dist) in through {
+            if witnesses.get(&to).is_none_or(|&around| around > dist) {
+                shortcuts.push((from, to, dist));
+            }
+        }
+    }
+    shortcuts
+}
+
+/// Distances from `start` that avoid `skip`, settling nodes up to `limit` away.
+fn witness_search<N, D>(out: &Remaining<N, D>, start: N, skip: N, limit: D) -> HashMap<N, D>
+where
+    N: Hash + Eq + Copy,
+    D: Zero + Ord + Copy,
+{
+    let mut to_explore = PriorityQueue::new();
+    to_explore.push(start, Reverse(D::zero()));
+    let mut settled = HashMap::new();
+    while let Some((cur, Reverse(cur_distance))) = to_explore.pop() {
+        if cur_distance > limit {
+            break;
+        }
+        settled.insert(cur, cur_distance);
+        for (&next, &dist) in &out[&cur] {
+            if next == skip || settled.contains_key(&next) {
+                continue;
+            }
+            to_explore.push_increase(next, Reverse(cur_distance + dist));
+        }
+    }
+    settled
+}
+
+/// Dijkstra's algorithm over the edges of one direction of the hierarchy, settling everything it
+/// can reach. Returns the distances and the node each was reached from.
+fn upward_search<N, D>(edges: &HashMap<N, Vec<(N, D)>>, start: N) -> (HashMap<N, D>, HashMap<N, N>)
+where
+    N: Hash + Eq + Copy,
+    D: Zero + Ord + Copy,
+{
+    let mut to_explore = PriorityQueue::new();
+    to_explore.push(start, Reverse(D::zero()));
+    let mut settled = HashMap::new();
+    let mut prev = HashMap::new();
+    while let Some((cur, Reverse(cur_distance))) = to_explore.pop() {
+        settled.insert(cur, cur_distance);
+        for &(next, dist) in edges.get(&cur).into_iter().flatten() {
+            if settled.contains_key(&next) {
+                continue;
+            }
+            let start_to_next = cur_distance + dist;
+            let improves = to_explore
+                .get_priority(&next)
+                .map(|&Reverse(best)| start_to_next < best)
+                .unwrap_or(true);
+            if improves {
+                to_explore.push(next, Reverse(start_to_next));
+                prev.insert(next, cur);
+            }
+        }
+    }
+    (settled, prev)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::random_graph;
+    use crate::test_graphs::Cycles;
+    use crate::{distance, verify_shortest_path};
+
+    fn matches_dijkstra<G>(g: G, nodes: &[G::Node])
+    where
+        G: ReversibleVGraph,
+        G::Node: Hash + Eq + Copy + std::fmt::Debug,
+        G::Dist: Zero + Ord + Copy + std::fmt::Debug,
+    {
+        let hierarchy = ContractionHierarchy::build(&g, nodes);
+        for &start in nodes {
+            for &end in nodes {
+                let shortest = distance(&g, start, end);
+                match hierarchy.query(start, end) {
+                    None => assert_eq!(None, shortest, "{start:?} to {end:?}"),
+                    Some(path) => {
+                        assert_eq!(Some(&start), path.first());
+                        assert_eq!(Some(&end), path.last());
+                        let shortest = shortest.expect("The hierarchy found a path.");
+                        assert_eq!(Ok(()), verify_shortest_path(&g, &path, shortest));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn queries_match_dijkstra() {
+        let nodes: Vec<usize> = (1..=10).collect();
+        matches_dijkstra(Cycles {}, &nodes);
+        for seed in 0..20 {
+            let g = random_graph(25, 3, seed);
+            let nodes: Vec<usize> = g.nodes().collect();
+            matches_dijkstra(&g, &nodes);
+        }
+    }
+
+    #[test]
+    fn unknown_nodes_have_no_path() {
+        let hierarchy = ContractionHierarchy::build(Cycles {}, &[1, 2, 3]);
+        assert_eq!(Some(vec![1, 2]), hierarchy.query(1, 2));
+        assert_eq!(Some(vec![2]), hierarchy.query(2, 2));
+        // The edges out of 3 all lead outside of the hierarchy.
+        assert_eq!(None, hierarchy.query(3, 1));
+        assert_eq!(None, hierarchy.query(1, 4));
+    }
+}
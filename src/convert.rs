@@ -0,0 +1,59 @@
+use num::traits::NumCast;
+
+/// How far above a whole number a float may be and still count as that number, so that
+/// rounding error in something like a square root does not round up a whole step.
+const TOLERANCE: f64 = 1e-9;
+
+/// Rounds `x` up to an integer cost type.
+///
+/// Meant for float heuristics on graphs with integer distances. Every path on such a graph
+/// costs a whole number, so if `x` is at most the true cost then so is its ceiling, and a
+/// heuristic stays admissible. Values within a tiny tolerance above a whole number round down
+/// to it, so float error can not push an exact estimate over the true cost.
+///
+/// Panics if `x` is NaN or does not fit in `D`.
+pub fn ceil_to_int<D>(x: f64) -> D
+where
+    D: NumCast,
+{
+    D::from((x - TOLERANCE).ceil()).unwrap_or_else(|| panic!("{x} does not fit the cost type"))
+}
+
+/// Turns a float heuristic into one for integer costs with `ceil_to_int`.
+pub fn ceil_heuristic<N, D, H>(heuristic: H) -> impl Fn(N) -> D
+where
+    D: NumCast,
+    H: Fn(N) -> f64,
+{
+    move |node| ceil_to_int(heuristic(node))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{a_star_search, path_length, GridGraph};
+
+    #[test]
+    fn rounds_up() {
+        assert_eq!(3, ceil_to_int::<u32>(2.2));
+        assert_eq!(2, ceil_to_int::<u32>(2.0));
+        assert_eq!(5, ceil_to_int::<usize>(25f64.sqrt()));
+        assert_eq!(0, ceil_to_int::<i64>(-0.5));
+    }
+
+    #[test]
+    fn ceiled_euclidean_on_grid() {
+        let mut g = GridGraph::new(8, 8);
+        for y in 1..8 {
+            g.block((4, y));
+        }
+        let end = (7, 7);
+        let euclidean = |(x, y): (usize, usize)| {
+            let (dx, dy) = (x as f64 - end.0 as f64, y as f64 - end.1 as f64);
+            (dx * dx + dy * dy).sqrt()
+        };
+        let path = a_star_search(&g, (0, 7), |n| n == end, ceil_heuristic(euclidean)).unwrap();
+        let shortest = a_star_search(&g, (0, 7), |n| n == end, |_| 0).unwrap();
+        assert_eq!(path_length(&g, shortest), path_length(&g, path));
+    }
+}
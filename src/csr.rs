@@ -0,0 +1,139 @@
+use crate::VGraph;
+
+/// A static graph over the nodes `0..num_nodes`, stored in compressed sparse row form.
+///
+/// All edges live in one array sorted by source node, so reading a node's neighbors touches a
+/// single contiguous slice. Suited to large graphs that are built once and searched many times;
+/// use `AdjacencyListGraph` when the graph needs to change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrGraph<D> {
+    // Edges of node `n` are at `offsets[n]..offsets[n + 1]`.
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+    dists: Vec<D>,
+}
+
+impl<D> CsrGraph<D>
+where
+    D: Copy,
+{
+    /// Builds the graph from `(from, to, dist)` edges. Each node's edges keep the order they
+    /// were given in.
+    ///
+    /// Panics if an edge refers to a node outside of `0..num_nodes`.
+    pub fn from_edges(edges: &[(usize, usize, D)], num_nodes: usize) -> Self {
+        let mut offsets = vec![0; num_nodes + 1];
+        for &(from, to, _) in edges {
+            assert!(
+                from < num_nodes && to < num_nodes,
+                "edge ({from}, {to}) is outside of the {num_nodes} nodes"
+            );
+            offsets[from + 1] += 1;
+        }
+        for i in 0..num_nodes {
+            offsets[i + 1] += offsets[i];
+        }
+
+        let mut next_slot = offsets.clone();
+        let mut slots: Vec<Option<(usize, D)>> = vec![None; edges.len()];
+        for &(from, to, dist) in edges {
+            slots[next_slot[from]] = Some((to, dist));
+            next_slot[from] += 1;
+        }
+        let (targets, dists) = slots
+            .into_iter()
+            .map(|slot| slot.expect("Every edge slot is filled exactly once."))
+            .unzip();
+
+        CsrGraph {
+            offsets,
+            targets,
+            dists,
+        }
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn num_edges(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// The targets of the edges leaving `node`.
+    pub fn neighbors(&self, node: usize) -> &[usize] {
+        &self.targets[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    /// The distances of the edges leaving `node`, in the same order as `neighbors`.
+    pub fn neighbor_dists(&self, node: usize) -> &[D] {
+        &self.dists[self.offsets[node]..self.offsets[node + 1]]
+    }
+}
+
+impl<D> VGraph for CsrGraph<D>
+where
+    D: Copy,
+{
+    type Node = usize;
+
+    type Dist = D;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.neighbors(node).to_vec()
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        let i = self
+            .neighbors(from)
+            .iter()
+            .position(|&next| next == to)
+            .expect("dist is only defined for nodes connected by an edge.");
+        self.neighbor_dists(from)[i]
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.num_nodes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::random_graph;
+    use crate::{a_star_search, dijkstra_iter, path_length};
+    use std::collections::HashMap;
+
+    #[test]
+    fn from_edges_groups_by_source() {
+        let g = CsrGraph::from_edges(&[(2, 0, 7), (0, 1, 1), (0, 2, 4), (2, 1, 3)], 4);
+        assert_eq!(4, g.num_nodes());
+        assert_eq!(4, g.num_edges());
+        assert_eq!(&[1, 2], g.neighbors(0));
+        assert_eq!(&[0, 1], g.neighbors(2));
+        assert!(g.neighbors(3).is_empty());
+        assert_eq!(3, g.dist(2, 1));
+    }
+
+    #[test]
+    fn matches_adjacency_list() {
+        let list = random_graph(40, 3, 9);
+        let mut edges = Vec::new();
+        for node in 0..40 {
+            for &(to, dist) in list.edges_from(node) {
+                edges.push((node, to, dist));
+            }
+        }
+        let csr = CsrGraph::from_edges(&edges, 40);
+
+        let list_dists: HashMap<usize, u32> = dijkstra_iter(&list, 0).collect();
+        let csr_dists: HashMap<usize, u32> = dijkstra_iter(&csr, 0).collect();
+        assert_eq!(list_dists, csr_dists);
+
+        for end in [5, 17, 39] {
+            let list_path = a_star_search(&list, 0, |n| n == end, |_| 0).unwrap();
+            let csr_path = a_star_search(&csr, 0, |n| n == end, |_| 0).unwrap();
+            assert_eq!(path_length(&list, list_path), path_length(&csr, csr_path));
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::VGraph;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Nodes whose removal splits the part of the graph they are in.
+///
+/// The graph is treated as undirected, an edge in either direction connects two nodes. Only
+/// `nodes` and the edges between them are considered. Returned in the order of `nodes`.
+pub fn articulation_points<G>(g: G, nodes: &[G::Node]) -> Vec<G::Node>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    let cuts = low_links(g, nodes).points;
+    nodes.iter().copied().filter(|n| cuts.contains(n)).collect()
+}
+
+/// Edges whose removal splits the part of the graph they are in.
+///
+/// The graph is treated as undirected, as in `articulation_points`. Each bridge is given once,
+/// as `(a, b)` where `a` was reached first by a depth first search over `nodes` in order.
+pub fn bridges<G>(g: G, nodes: &[G::Node]) -> Vec<(G::Node, G::Node)>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    low_links(g, nodes).bridges
+}
+
+struct Cuts<N> {
+    points: HashSet<N>,
+    bridges: Vec<(N, N)>,
+}
+
+/// Tarjan's low link depth first search, with an explicit stack so deep graphs do not overflow.
+fn low_links<G>(g: G, nodes: &[G::Node]) -> Cuts<G::Node>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    let mut neighbors: HashMap<G::Node, Vec<G::Node>> =
+        nodes.iter().map(|&n| (n, Vec::new())).collect();
+    let mut edges = HashSet::new();
+    for &node in nodes {
+        for next in g.out_edges(node) {
+            if next != node && neighbors.contains_key(&next) && edges.insert((node, next)) {
+                edges.insert((next, node));
+                neighbors.get_mut(&node).unwrap().push(next);
+                neighbors.get_mut(&next).unwrap().push(node);
+            }
+        }
+    }
+
+    let mut cuts = Cuts {
+        points: HashSet::new(),
+        bridges: Vec::new(),
+    };
+    // Order of discovery, and the earliest discovered node reachable by going down the tree
+    // and then taking one edge back up.
+    let mut discovered: HashMap<G::Node, usize> = HashMap::new();
+    let mut low: HashMap<G::Node, usize> = HashMap::new();
+    for &root in nodes {
+        if discovered.contains_key(&root) {
+            continue;
+        }
+        discovered.insert(root, discovered.len());
+        low.insert(root, discovered[&root]);
+        let mut root_children = 0;
+        // Each entry is a node, its parent in the tree, and how many neighbors it has tried.
+        let mut stack: Vec<(G::Node, Option<G::Node>, usize)> = vec![(root, None, 0)];
+        while let Some(top) = stack.last_mut() {
+            let (cur, parent, tried) = *top;
+            if let Some(&next) = neighbors[&cur].get(tried) {
+                top.2 += 1;
+                if Some(next) == parent {
+                    continue;
+                }
+                match discovered.get(&next) {
+                    Some(&order) => {
+                        let cur_low = low.get_mut(&cur).unwrap();
+                        *cur_low = (*cur_low).min(order);
+                    }
+                    None => {
+                        let order = discovered.len();
+                        discovered.insert(next, order);
+                        low.insert(next, order);
+                        if cur == root {
+                            root_children += 1;
+                        }
+                        stack.push((next, Some(cur), 0));
+                    }
+                }
+                continue;
+            }
+
+            stack.pop();
+            let Some(parent) = parent else {
+                continue;
+            };
+            let cur_low = low[&cur];
+            let parent_low = low.get_mut(&parent).unwrap();
+            *parent_low = (*parent_low).min(cur_low);
+            if cur_low > discovered[&parent] {
+                cuts.bridges.push((parent, cur));
+            }
+            if parent != root && cur_low >= discovered[&parent] {
+                cuts.points.insert(parent);
+            }
+        }
+        if root_children > 1 {
+            cuts.points.insert(root);
+        }
+    }
+    cuts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::Cycles;
+    use crate::AdjacencyListGraph;
+
+    /// Two triangles joined through node 2, which also has a tail 2 - 5.
+    fn bowtie() -> AdjacencyListGraph<usize, u32> {
+        let mut g = AdjacencyListGraph::new();
+        for (a, b) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2), (2, 5)] {
+            g.add_edge(a, b, 1);
+        }
+        g
+    }
+
+    #[test]
+    fn bowtie_cut_vertex() {
+        let nodes = [0, 1, 2, 3, 4, 5];
+        assert_eq!(vec![2], articulation_points(&bowtie(), &nodes));
+        assert_eq!(vec![(2, 5)], bridges(&bowtie(), &nodes));
+    }
+
+    #[test]
+    fn chain_into_cycles() {
+        // 7 - 8 - 9 - 10 hangs off the rest, and 6 only touches 2.
+        let nodes: Vec<usize> = (1..=10).collect();
+        assert_eq!(vec![2, 8, 9, 10], articulation_points(Cycles {}, &nodes));
+        let mut found = bridges(Cycles {}, &nodes);
+        found.sort();
+        assert_eq!(vec![(2, 6), (8, 7), (9, 8), (10, 9)], found);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{back_track, AdjacencyListGraph, SearchError, VGraph};
+use num::traits::Zero;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// The most expensive path from `start` to `end` in a directed acyclic graph.
+///
+/// Longest path is NP-hard on general graphs, but on a DAG it is a single pass over the nodes in
+/// topological order. Only `nodes` and the edges between them are considered, and if those edges
+/// contain a cycle this returns `SearchError::Cycle` rather than a path. Returns `Ok(None)` when
+/// `end` can not be reached from `start`.
+pub fn longest_path_dag<G>(
+    g: G,
+    start: G::Node,
+    end: G::Node,
+    nodes: &[G::Node],
+) -> Result<Option<Vec<G::Node>>, SearchError<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let allowed: HashSet<G::Node> = nodes.iter().copied().collect();
+    let order = topological_order(&g, nodes)?;
+
+    let mut longest: HashMap<G::Node, G::Dist> = HashMap::new();
+    let mut prev: HashMap<G::Node, G::Node> = HashMap::new();
+    longest.insert(start, G::Dist::zero());
+    for cur in order {
+        // Every edge into `cur` has been seen by now, so its distance is final.
+        let Some(&cur_dist) = longest.get(&cur) else {
+            continue;
+        };
+        for next in g.out_edges(cur) {
+            if !allowed.contains(&next) {
+                continue;
+            }
+            let through_cur = cur_dist + g.dist(cur, next);
+            if longest.get(&next).is_none_or(|&known| through_cur > known) {
+                longest.insert(next, through_cur);
+                prev.insert(next, cur);
+            }
+        }
+    }
+
+    if !longest.contains_key(&end) {
+        return Ok(None);
+    }
+    Ok(Some(back_track(&prev, end)))
+}
+
+type Reduced<N, D> = Result<AdjacencyListGraph<N, D>, SearchError<N>>;
+
+/// The fewest edges of a directed acyclic graph that still connect the same pairs of nodes.
+///
+/// An edge is dropped when its end can also be reached from its start by a longer path, like a
+/// dependency that is already implied by another one. Kept edges keep their distance. Only
+/// `nodes` and the edges between them are considered, every one of `nodes` is in the result
+/// even if it loses all its edges. Returns `SearchError::Cycle` if the edges contain a cycle,
+/// since a cyclic graph has no unique reduction.
+pub fn transitive_reduction<G>(g: G, nodes: &[G::Node]) -> Reduced<G::Node, G::Dist>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    let order = topological_order(&g, nodes)?;
+    let position: HashMap<G::Node, usize> =
+        order.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+
+    let mut reduced = AdjacencyListGraph::new();
+    // Every node reachable from each node already handled.
+    let mut reachable: HashMap<G::Node, HashSet<G::Node>> = HashMap::new();
+    for &cur in order.iter().rev() {
+        reduced.add_node(cur);
+        let mut next_nodes: Vec<G::Node> = g
+            .out_edges(cur)
+            .into_iter()
+            .filter(|next| position.contains_key(next))
+            .collect();
+        // A node reached through another neighbor comes after it, so nearer ones go first.
+        next_nodes.sort_by_key(|next| position[next]);
+        let mut reached = HashSet::new();
+        for next in next_nodes {
+            if !reached.insert(next) {
+                continue;
+            }
+            reduced.add_edge(cur, next, g.dist(cur, next));
+            reached.extend(reachable[&next].iter().copied());
+        }
+        reachable.insert(cur, reached);
+    }
+    Ok(reduced)
+}
+
+/// Orders `nodes` so every edge between them goes from an earlier node to a later one, using
+/// Kahn's algorithm. Edges leading outside of `nodes` are ignored.
+pub(crate) fn topological_order<G>(
+    g: G,
+    nodes: &[G::Node],
+) -> Result<Vec<G::Node>, SearchError<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    let mut in_degree: HashMap<G::Node, usize> = nodes.iter().map(|&n| (n, 0)).collect();
+    for &node in nodes {
+        for next in g.out_edges(node) {
+            if let Some(degree) = in_degree.get_mut(&next) {
+                *degree += 1;
+            }
+        }
+    }
+
+    let mut ready: VecDeque<G::Node> = nodes
+        .iter()
+        .copied()
+        .filter(|n| in_degree[n] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(nodes.len());
+    while let Some(cur) = ready.pop_front() {
+        order.push(cur);
+        for next in g.out_edges(cur) {
+            if let Some(degree) = in_degree.get_mut(&next) {
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.push_back(next);
+                }
+            }
+        }
+    }
+
+    // Whatever never became ready is on a cycle, or downstream of one.
+    if order.len() < in_degree.len() {
+        let placed: HashSet<G::Node> = order.iter().copied().collect();
+        let stuck = nodes
+            .iter()
+            .copied()
+            .find(|n| !placed.contains(n))
+            .expect("Some node was not placed.");
+        return Err(SearchError::Cycle(stuck));
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::{Cycles, Ex};
+    use crate::{path_length, AdjacencyListGraph};
+
+    #[test]
+    fn longest_path_on_ex() {
+        assert_eq!(
+            Ok(Some(vec![1, 2, 3])),
+            longest_path_dag(Ex::new(), 1, 3, &[1, 2, 3])
+        );
+        assert_eq!(Ok(None), longest_path_dag(Ex::new(), 3, 1, &[1, 2, 3]));
+    }
+
+    #[test]
+    fn takes_the_expensive_route() {
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(0, 1, 1);
+        g.add_edge(1, 3, 1);
+        g.add_edge(0, 2, 2);
+        g.add_edge(2, 3, 4);
+        g.add_edge(0, 3, 5);
+        let path = longest_path_dag(&g, 0, 3, &[0, 1, 2, 3]).unwrap().unwrap();
+        assert_eq!(vec![0, 2, 3], path);
+        assert_eq!(6, path_length(&g, path));
+    }
+
+    #[test]
+    fn shortcut_edge_is_removed() {
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(0, 1, 1);
+        g.add_edge(1, 2, 1);
+        g.add_edge(2, 3, 1);
+        g.add_edge(0, 3, 9);
+        g.add_edge(1, 3, 9);
+        g.add_edge(0, 4, 2);
+        let reduced = transitive_reduction(&g, &[0, 1, 2, 3, 4]).unwrap();
+
+        let mut expected = AdjacencyListGraph::new();
+        expected.add_edge(0, 1, 1);
+        expected.add_edge(1, 2, 1);
+        expected.add_edge(2, 3, 1);
+        expected.add_edge(0, 4, 2);
+        for node in 0..5 {
+            let mut edges = reduced.edges_from(node).to_vec();
+            edges.sort();
+            assert_eq!(expected.edges_from(node), edges, "{node}");
+        }
+        assert_eq!(4, reduced.num_edges());
+
+        // Edges to nodes left out are dropped.
+        let reduced = transitive_reduction(&g, &[0, 3]).unwrap();
+        assert_eq!(&[(3, 9)], reduced.edges_from(0));
+    }
+
+    #[test]
+    fn cycle_is_an_error() {
+        let nodes: Vec<usize> = (1..=10).collect();
+        assert!(matches!(
+            longest_path_dag(Cycles {}, 1, 10, &nodes),
+            Err(SearchError::Cycle(_))
+        ));
+    }
+
+    #[test]
+    fn transitive_reduction_rejects_cycles() {
+        let nodes: Vec<usize> = (1..=10).collect();
+        assert!(matches!(
+            transitive_reduction(Cycles {}, &nodes),
+            Err(SearchError::Cycle(_))
+        ));
+    }
+}
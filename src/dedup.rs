@@ -0,0 +1,98 @@
+use crate::VGraph;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// Wraps a graph and removes repeated neighbors from `out_edges`, keeping the first occurrence.
+///
+/// Searches return the same results with or without duplicate edges, but every duplicate is
+/// relaxed again, which wastes work and skews any counts of expansions or `dist` calls.
+/// Deduplicating costs a `HashSet` per `out_edges` call, so only wrap graphs that actually
+/// produce duplicates.
+pub struct DedupEdges<G> {
+    g: G,
+}
+
+impl<G> DedupEdges<G> {
+    pub fn new(g: G) -> Self {
+        DedupEdges { g }
+    }
+}
+
+impl<G> VGraph for DedupEdges<G>
+where
+    G: VGraph,
+    G::Node: Eq + Hash + Copy,
+{
+    type Node = G::Node;
+
+    type Dist = G::Dist;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        let mut seen = HashSet::new();
+        let mut neighbors = self.g.out_edges(node);
+        neighbors.retain(|next| seen.insert(*next));
+        neighbors
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        self.g.dist(from, to)
+    }
+
+    fn is_terminal(&self, node: Self::Node) -> bool {
+        self.g.is_terminal(node)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.g.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{a_star_search, ExpansionRecorder, GraphCall};
+
+    struct Duplicates {}
+
+    impl VGraph for Duplicates {
+        type Node = usize;
+
+        type Dist = usize;
+
+        fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+            match node {
+                1 => vec![2, 2, 3, 2],
+                2 => vec![4, 4],
+                3 => vec![4],
+                _ => vec![],
+            }
+        }
+
+        fn dist(&self, _from: Self::Node, _to: Self::Node) -> Self::Dist {
+            1
+        }
+    }
+
+    fn count_dist_calls(log: &[GraphCall<usize, usize>]) -> usize {
+        log.iter()
+            .filter(|call| matches!(call, GraphCall::Dist { .. }))
+            .count()
+    }
+
+    #[test]
+    fn out_edges_are_unique() {
+        assert_eq!(vec![2, 3], DedupEdges::new(Duplicates {}).out_edges(1));
+    }
+
+    #[test]
+    fn dedup_reduces_relaxations() {
+        let plain = ExpansionRecorder::new(Duplicates {});
+        let deduped = ExpansionRecorder::new(DedupEdges::new(Duplicates {}));
+        let plain_path = a_star_search(&plain, 1, |n| n == 4, |_| 0);
+        let deduped_path = a_star_search(&deduped, 1, |n| n == 4, |_| 0);
+
+        assert_eq!(Some(vec![1, 2, 4]), plain_path);
+        assert_eq!(plain_path, deduped_path);
+        assert!(count_dist_calls(&deduped.log()) < count_dist_calls(&plain.log()));
+    }
+}
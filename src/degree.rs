@@ -0,0 +1,120 @@
+use crate::{ReversibleVGraph, VGraph};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A summary of how many edges lead out of and into a set of nodes.
+///
+/// Every field is zero for an empty set of nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DegreeStats {
+    pub min_out: usize,
+    pub max_out: usize,
+    pub mean_out: f64,
+    pub min_in: usize,
+    pub max_in: usize,
+    pub mean_in: f64,
+}
+
+impl DegreeStats {
+    fn from_degrees(out: &[usize], into: &[usize]) -> Self {
+        let mean = |degrees: &[usize]| {
+            if degrees.is_empty() {
+                0.0
+            } else {
+                degrees.iter().sum::<usize>() as f64 / degrees.len() as f64
+            }
+        };
+        DegreeStats {
+            min_out: out.iter().copied().min().unwrap_or(0),
+            max_out: out.iter().copied().max().unwrap_or(0),
+            mean_out: mean(out),
+            min_in: into.iter().copied().min().unwrap_or(0),
+            max_in: into.iter().copied().max().unwrap_or(0),
+            mean_in: mean(into),
+        }
+    }
+}
+
+/// Degree statistics over `nodes`, a quick look at the shape of a graph.
+///
+/// Without `in_edges`, a node's in degree only counts the edges from other nodes in `nodes`,
+/// so pass every node of the graph or use `degree_stats_reversible`. Each node should only be
+/// listed once.
+pub fn degree_stats<G>(g: G, nodes: &[G::Node]) -> DegreeStats
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    let mut in_degree: HashMap<G::Node, usize> = nodes.iter().map(|&n| (n, 0)).collect();
+    let out: Vec<usize> = nodes
+        .iter()
+        .map(|&node| {
+            let neighbors = g.out_edges(node);
+            for next in &neighbors {
+                if let Some(count) = in_degree.get_mut(next) {
+                    *count += 1;
+                }
+            }
+            neighbors.len()
+        })
+        .collect();
+    let into: Vec<usize> = nodes.iter().map(|node| in_degree[node]).collect();
+    DegreeStats::from_degrees(&out, &into)
+}
+
+/// `degree_stats` where in degrees count every edge into a node, including those from nodes
+/// outside of `nodes`.
+pub fn degree_stats_reversible<G>(g: G, nodes: &[G::Node]) -> DegreeStats
+where
+    G: ReversibleVGraph,
+    G::Node: Copy,
+{
+    let out: Vec<usize> = nodes.iter().map(|&n| g.out_edges(n).len()).collect();
+    let into: Vec<usize> = nodes.iter().map(|&n| g.in_edges(n).len()).collect();
+    DegreeStats::from_degrees(&out, &into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::Cycles;
+
+    #[test]
+    fn stats_of_cycles() {
+        let nodes: Vec<usize> = (1..=10).collect();
+        let expected = DegreeStats {
+            min_out: 1,
+            max_out: 2,
+            mean_out: 1.4,
+            min_in: 0,
+            max_in: 2,
+            mean_in: 1.4,
+        };
+        assert_eq!(expected, degree_stats(Cycles {}, &nodes));
+        assert_eq!(expected, degree_stats_reversible(Cycles {}, &nodes));
+    }
+
+    #[test]
+    fn subset_counts_edges_from_outside_only_when_reversible() {
+        let out_only = degree_stats(Cycles {}, &[1, 2, 3]);
+        assert_eq!(
+            (0, 2, 1.0),
+            (out_only.min_in, out_only.max_in, out_only.mean_in)
+        );
+        let reversible = degree_stats_reversible(Cycles {}, &[1, 2, 3]);
+        assert_eq!(
+            (2, 2, 2.0),
+            (reversible.min_in, reversible.max_in, reversible.mean_in)
+        );
+        assert_eq!(
+            (2, 2, 2.0),
+            (out_only.min_out, out_only.max_out, out_only.mean_out)
+        );
+    }
+
+    #[test]
+    fn empty_set_is_all_zero() {
+        let stats = degree_stats(Cycles {}, &[]);
+        assert_eq!((0, 0, 0.0), (stats.max_out, stats.max_in, stats.mean_out));
+    }
+}
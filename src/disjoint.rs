@@ -0,0 +1,294 @@
+use crate::{back_track, dijkstra_iter, DijkstraIter, VGraph};
+use num::traits::Zero;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::Sub;
+
+/// An edge as its `(from, to)` nodes.
+type Edge<N> = (N, N);
+
+/// Up to `k` paths from `start` to `end` that share no edge, with the least total cost, cheapest
+/// path first.
+///
+/// Taking the shortest path and then searching again without its edges can block every other
+/// route, so this uses Suurballe's algorithm instead. Each round finds a shortest path through
+/// the residual graph, where an edge already used can be followed backward to reroute an
+/// earlier path. Distances are reweighted by node potentials between rounds so they stay
+/// non-negative for Dijkstra. Fewer than `k` paths are returned when no more exist. The paths
+/// may share nodes, only edges are exclusive. Paths of equal cost come out in no particular
+/// order.
+///
+/// Edges must cost more than zero, or a path may loop.
+pub fn edge_disjoint_paths<G>(g: G, start: G::Node, end: G::Node, k: usize) -> Vec<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy + Sub<Output = G::Dist>,
+{
+    if start == end {
+        return if k == 0 {
+            Vec::new()
+        } else {
+            vec![vec![start]]
+        };
+    }
+    let mut flow: HashSet<Edge<G::Node>> = HashSet::new();
+    let mut potential = Potentials {
+        known: HashMap::new(),
+        unseen: G::Dist::zero(),
+    };
+    for _ in 0..k {
+        let residual = Residual {
+            g: &g,
+            flow: &flow,
+            potential: &potential,
+        };
+        let mut settle = dijkstra_iter(&residual, start);
+        if !settle.any(|(node, _)| node == end) {
+            break;
+        }
+        let DijkstraIter { settled, prev, .. } = settle;
+        let path = back_track(&prev, end);
+        let mut changes = Vec::new();
+        for step in path.windows(2) {
+            let (from, to) = (step[0], step[1]);
+            if residual.uses_backward(from, to) {
+                changes.push(((to, from), false));
+            } else {
+                changes.push(((from, to), true));
+            }
+        }
+        for (edge, add) in changes {
+            if add {
+                flow.insert(edge);
+            } else {
+                flow.remove(&edge);
+            }
+        }
+        potential.update(&settled, settled[&end]);
+    }
+
+    let mut out_flow: HashMap<G::Node, Vec<G::Node>> = HashMap::new();
+    for &(from, to) in &flow {
+        out_flow.entry(from).or_default().push(to);
+    }
+    let mut paths: Vec<(G::Dist, Vec<G::Node>)> = Vec::new();
+    while let Some(next) = out_flow.get_mut(&start).and_then(Vec::pop) {
+        let mut path = vec![start, next];
+        let mut cost = g.dist(start, next);
+        let mut cur = next;
+        while cur != end {
+            let next = out_flow
+                .get_mut(&cur)
+                .and_then(Vec::pop)
+                .expect("Flow into a node should also leave it.");
+            cost = cost + g.dist(cur, next);
+            path.push(next);
+            cur = next;
+        }
+        paths.push((cost, path));
+    }
+    paths.sort_by_key(|&(cost, _)| cost);
+    paths.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Node potentials, the distance from the start summed over every round so far.
+///
+/// Nodes never reached have all moved by the same amount, `unseen`.
+struct Potentials<N, D> {
+    known: HashMap<N, D>,
+    unseen: D,
+}
+
+impl<N, D> Potentials<N, D>
+where
+    N: Hash + Eq + Copy,
+    D: Zero + Copy,
+{
+    fn get(&self, node: N) -> D {
+        self.known.get(&node).copied().unwrap_or(self.unseen)
+    }
+
+    /// Adds each settled node's distance to its potential. The search stopped at the end, so
+    /// every other node is at least `end_dist` away and moves by that much.
+    fn update(&mut self, settled: &HashMap<N, D>, end_dist: D) {
+        let moved: Vec<(N, D)> = settled
+            .iter()
+            .map(|(&node, &dist)| (node, self.get(node) + dist))
+            .collect();
+        for (node, p) in self.known.iter_mut() {
+            if !settled.contains_key(node) {
+                *p = *p + end_dist;
+            }
+        }
+        self.unseen = self.unseen + end_dist;
+        self.known.extend(moved);
+    }
+}
+
+/// The edges without flow, and the edges with flow reversed, with distances reduced by the
+/// potentials so none are negative.
+struct Residual<'a, G>
+where
+    G: VGraph,
+{
+    g: &'a G,
+    flow: &'a HashSet<Edge<G::Node>>,
+    potential: &'a Potentials<G::Node, G::Dist>,
+}
+
+impl<G> Residual<'_, G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy + Sub<Output = G::Dist>,
+{
+    /// Reduced distance of following the flow on `to -> from` backward, if there is any.
+    fn backward(&self, from: G::Node, to: G::Node) -> Option<G::Dist> {
+        self.flow.contains(&(to, from)).then(|| {
+            // The edge was on a shortest path, so `from` is at least its distance past `to`.
+            self.potential.get(from) - self.potential.get(to) - self.g.dist(to, from)
+        })
+    }
+
+    /// Reduced distance of the edge `from -> to`, if it exists and has no flow.
+    fn forward(&self, from: G::Node, to: G::Node) -> Option<G::Dist> {
+        if self.flow.contains(&(from, to)) {
+            return None;
+        }
+        // Listed for its backward edge alone, unless the graph has the edge too.
+        if self.flow.contains(&(to, from)) && !self.g.out_edges(from).contains(&to) {
+            return None;
+        }
+        Some(self.g.dist(from, to) + self.potential.get(from) - self.potential.get(to))
+    }
+
+    /// True if the cheapest way along `from -> to` cancels flow rather than adding it.
+    fn uses_backward(&self, from: G::Node, to: G::Node) -> bool {
+        match (self.backward(from, to), self.forward(from, to)) {
+            (Some(back), Some(fwd)) => back <= fwd,
+            (back, _) => back.is_some(),
+        }
+    }
+}
+
+impl<G> VGraph for Residual<'_, G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy + Sub<Output = G::Dist>,
+{
+    type Node = G::Node;
+
+    type Dist = G::Dist;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        let mut neighbors = self.g.out_edges(node);
+        neighbors.retain(|&next| !self.flow.contains(&(node, next)));
+        neighbors.extend(
+            self.flow
+                .iter()
+                .filter(|&&(_, to)| to == node)
+                .map(|&(from, _)| from),
+        );
+        neighbors
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        match (self.backward(from, to), self.forward(from, to)) {
+            (Some(back), Some(fwd)) => back.min(fwd),
+            (back, fwd) => back.or(fwd).expect("Every residual edge has a distance."),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.g.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::random_graph;
+    use crate::test_graphs::Cycles;
+    use crate::{a_star_search, is_valid_path, max_flow, AdjacencyListGraph};
+    use std::fmt::Debug;
+
+    fn assert_edge_disjoint<N: Hash + Eq + Copy + Debug>(paths: &[Vec<N>]) {
+        let mut used = HashSet::new();
+        for path in paths {
+            for step in path.windows(2) {
+                assert!(used.insert((step[0], step[1])), "{paths:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn reroutes_around_the_trap() {
+        // The shortest path 0 1 4 5 blocks both other routes to 5, 0 1 3 5 and 0 2 4 5 are the
+        // only disjoint pair.
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(0, 1, 1);
+        g.add_edge(1, 4, 1);
+        g.add_edge(4, 5, 1);
+        g.add_edge(0, 2, 2);
+        g.add_edge(2, 4, 2);
+        g.add_edge(1, 3, 2);
+        g.add_edge(3, 5, 2);
+
+        assert_eq!(
+            a_star_search(&g, 0, |n| n == 5, |_| 0)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            edge_disjoint_paths(&g, 0, 5, 1)
+        );
+        let mut paths = edge_disjoint_paths(&g, 0, 5, 3);
+        assert_edge_disjoint(&paths);
+        // Both cost 5, so they may come in either order.
+        paths.sort();
+        assert_eq!(vec![vec![0, 1, 3, 5], vec![0, 2, 4, 5]], paths);
+    }
+
+    #[test]
+    fn independent_routes() {
+        let mut g = AdjacencyListGraph::new();
+        for route in [[0, 1, 2, 9], [0, 3, 4, 9], [0, 1, 4, 9]] {
+            for step in route.windows(2) {
+                g.add_edge(step[0], step[1], 1);
+            }
+        }
+        g.add_edge(0, 9, 5);
+        let paths = edge_disjoint_paths(&g, 0, 9, 5);
+        assert_edge_disjoint(&paths);
+        // 0 has three edges out, so there are no more than three paths.
+        assert_eq!(3, paths.len());
+        assert_eq!(vec![0, 9], paths[2]);
+        assert!(edge_disjoint_paths(&g, 0, 9, 0).is_empty());
+        assert!(edge_disjoint_paths(&g, 9, 0, 2).is_empty());
+        assert_eq!(vec![vec![4]], edge_disjoint_paths(&g, 4, 4, 2));
+
+        // The only other edge into 10 comes from 9, which 1 can not reach.
+        assert_eq!(
+            vec![vec![1, 3, 4, 10]],
+            edge_disjoint_paths(Cycles {}, 1, 10, 2)
+        );
+    }
+
+    #[test]
+    fn as_many_paths_as_unit_flow() {
+        for seed in 0..5 {
+            let g = random_graph(40, 3, seed);
+            let paths = edge_disjoint_paths(&g, 0, 1, 10);
+            assert_edge_disjoint(&paths);
+            assert_eq!(
+                max_flow(&g, 0, 1, |_, _| 1),
+                paths.len() as u64,
+                "seed {seed}"
+            );
+            for path in &paths {
+                assert_eq!((Some(&0), Some(&1)), (path.first(), path.last()));
+                assert!(is_valid_path(&g, path));
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::back_track;
+use num::traits::Zero;
+use priority_queue::PriorityQueue;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Virtual graph that may change itself while it is explored, such as a map that reveals what
+/// is around each place once it is visited.
+pub trait DynamicVGraph {
+    type Node;
+
+    // Measure of the distance between nodes
+    type Dist;
+
+    /// The edges out of `node` as currently known. Is only called once per node by a search,
+    /// when the node is expanded.
+    fn out_edges(&mut self, node: Self::Node) -> Vec<Self::Node>;
+
+    fn dist(&mut self, from: Self::Node, to: Self::Node) -> Self::Dist;
+}
+
+/// A* search over a graph that can change as it is explored.
+///
+/// Nodes are expanded once, in order of their estimated cost as for `a_star_search`, and the
+/// graph may change during each `out_edges` or `dist` call. Changes to the edges of nodes that
+/// were already expanded are never seen. The path is only guaranteed to be the shortest when
+/// those changes can not make an expanded node cheaper to reach, so costs must not drop below
+/// the distances already settled. Otherwise the path is still a valid path, as the graph was
+/// when each of its edges was followed.
+pub fn a_star_dynamic<G, F, H>(
+    g: &mut G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+) -> Option<Vec<G::Node>>
+where
+    G: DynamicVGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    let mut to_explore = PriorityQueue::new();
+    to_explore.push(start, Reverse(heuristic(start)));
+    let mut prev: HashMap<G::Node, G::Node> = HashMap::new();
+    let mut dist_from_start: HashMap<G::Node, G::Dist> = HashMap::new();
+    dist_from_start.insert(start, G::Dist::zero());
+    let mut expanded: HashSet<G::Node> = HashSet::new();
+
+    while let Some((cur, _priority)) = to_explore.pop() {
+        if is_end(cur) {
+            return Some(back_track(&prev, cur));
+        }
+        expanded.insert(cur);
+
+        let cur_distance = dist_from_start[&cur];
+        for next in g.out_edges(cur) {
+            if expanded.contains(&next) {
+                continue;
+            }
+            let start_to_next = cur_distance + g.dist(cur, next);
+            let improves = dist_from_start
+                .get(&next)
+                .map(|&best| start_to_next < best)
+                .unwrap_or(true);
+            if improves {
+                dist_from_start.insert(next, start_to_next);
+                prev.insert(next, cur);
+                to_explore.push(next, Reverse(start_to_next + heuristic(next)));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A line of nodes 0 to 10. The shortcut from 2 to 9 is only known once 3 has been visited.
+    struct Fog {
+        visited: Vec<u32>,
+    }
+
+    impl DynamicVGraph for Fog {
+        type Node = u32;
+
+        type Dist = u32;
+
+        fn out_edges(&mut self, node: Self::Node) -> Vec<Self::Node> {
+            self.visited.push(node);
+            let mut neighbors = vec![];
+            if node > 0 {
+                neighbors.push(node - 1);
+            }
+            if node < 10 {
+                neighbors.push(node + 1);
+            }
+            if node == 2 && self.visited.contains(&3) {
+                neighbors.push(9);
+            }
+            neighbors
+        }
+
+        fn dist(&mut self, _from: Self::Node, _to: Self::Node) -> Self::Dist {
+            1
+        }
+    }
+
+    #[test]
+    fn edges_revealed_while_searching() {
+        let mut fog = Fog { visited: vec![] };
+        let path = a_star_dynamic(&mut fog, 0, |n| n == 10, |_| 0);
+        // 2 was expanded before 3 revealed the shortcut, so the long way is taken.
+        assert_eq!(Some((0..=10).collect()), path);
+        assert_eq!((0..10).collect::<Vec<_>>(), fog.visited);
+
+        // The graph remembers what it revealed, so searching again finds the shortcut.
+        let path = a_star_dynamic(&mut fog, 0, |n| n == 10, |_| 0);
+        assert_eq!(Some(vec![0, 1, 2, 9, 10]), path);
+        assert_eq!(None, a_star_dynamic(&mut fog, 0, |n| n == 11, |_| 0));
+    }
+}
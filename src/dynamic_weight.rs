@@ -0,0 +1,113 @@
+use crate::{back_track, VGraph};
+use num::traits::Zero;
+use priority_queue::PriorityQueue;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Mul;
+
+/// Weighted A* where the heuristic weight depends on how many edges the path has so far.
+///
+/// Nodes are ordered by `g + weight(depth) * h`, with `depth` counting the edges from `start`
+/// along the best path found to the node. A weight that is large near the start and falls
+/// toward one pushes the search straight toward the goal early, then lets it tidy up the path
+/// as it gets close.
+///
+/// With an admissible heuristic, the path costs at most `w` times the shortest one, where `w` is
+/// the largest weight used and at least one. Weights of at most one always give the shortest
+/// path.
+pub fn a_star_dynamic_weight<G, F, H, W>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+    weight: W,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Mul<Output = G::Dist> + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+    W: Fn(usize) -> G::Dist,
+{
+    let mut to_explore = PriorityQueue::new();
+    to_explore.push(start, Reverse(weight(0) * heuristic(start)));
+    let mut prev: HashMap<G::Node, G::Node> = HashMap::new();
+    let mut dist_from_start: HashMap<G::Node, G::Dist> = HashMap::new();
+    dist_from_start.insert(start, G::Dist::zero());
+    let mut depth: HashMap<G::Node, usize> = HashMap::new();
+    depth.insert(start, 0);
+
+    while let Some((cur, _priority)) = to_explore.pop() {
+        if is_end(cur) {
+            return Some(back_track(&prev, cur));
+        }
+
+        let (cur_distance, cur_depth) = (dist_from_start[&cur], depth[&cur]);
+        for next in g.out_edges(cur) {
+            let start_to_next = cur_distance + g.dist(cur, next);
+            let improves = dist_from_start
+                .get(&next)
+                .map(|&best| start_to_next < best)
+                .unwrap_or(true);
+            if !improves {
+                continue;
+            }
+            prev.insert(next, cur);
+            dist_from_start.insert(next, start_to_next);
+            depth.insert(next, cur_depth + 1);
+            // A cheaper path can be deeper, and so weighted higher, so the priority is replaced
+            // rather than only ever raised.
+            let estimate = start_to_next + weight(cur_depth + 1) * heuristic(next);
+            to_explore.push(next, Reverse(estimate));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{distance, path_length, GridGraph};
+
+    #[test]
+    fn finds_valid_bounded_paths() {
+        let mut grid = GridGraph::new(12, 12);
+        for y in 0..10 {
+            grid.block((6, y));
+        }
+        let (start, end) = ((0, 0), (11usize, 0usize));
+        let manhattan = |(x, y): (usize, usize)| end.0.abs_diff(x) + end.1.abs_diff(y);
+        let shortest = distance(&grid, start, end).unwrap();
+
+        // Each weight function, along with the largest weight it gives.
+        let weights: [fn(usize) -> usize; 3] = [
+            |_| 1,
+            |depth| if depth < 8 { 3 } else { 1 },
+            |depth| 5usize.saturating_sub(depth / 4).max(1),
+        ];
+        for (weight, max_weight) in weights.into_iter().zip([1, 3, 5]) {
+            let path = a_star_dynamic_weight(&grid, start, |n| n == end, manhattan, weight)
+                .expect("The wall has a gap.");
+            assert_eq!(Some(&start), path.first());
+            assert_eq!(Some(&end), path.last());
+            assert!(path
+                .windows(2)
+                .all(|step| grid.out_edges(step[0]).contains(&step[1])));
+            let cost = path_length(&grid, path);
+            assert!(
+                cost <= max_weight * shortest,
+                "{cost} > {max_weight} * {shortest}"
+            );
+            if max_weight == 1 {
+                assert_eq!(shortest, cost);
+            }
+        }
+        assert_eq!(
+            None,
+            a_star_dynamic_weight(&grid, start, |n| n == (20, 20), |_| 0, |_| 2)
+        );
+    }
+}
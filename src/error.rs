@@ -0,0 +1,105 @@
+use std::error::Error;
+use std::fmt;
+
+/// Problems detected in the inputs to a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchError<N> {
+    /// The heuristic estimated a negative distance from this node to the goal.
+    NegativeHeuristic(N),
+    /// The graph was required to be acyclic, but this node is on a cycle.
+    Cycle(N),
+    /// A seeded distance for this node disagrees with the graph.
+    InconsistentSeed(N),
+}
+
+impl<N: fmt::Debug> fmt::Display for SearchError<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::NegativeHeuristic(node) => {
+                write!(
+                    f,
+                    "heuristic returned a negative estimate for node {node:?}"
+                )
+            }
+            SearchError::Cycle(node) => write!(f, "graph has a cycle through node {node:?}"),
+            SearchError::InconsistentSeed(node) => {
+                write!(
+                    f,
+                    "seeded distance for node {node:?} is not its true distance"
+                )
+            }
+        }
+    }
+}
+
+impl<N: fmt::Debug> Error for SearchError<N> {}
+
+/// Ways a path can fail `verify_shortest_path`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationError<N, D> {
+    /// The path has no nodes.
+    EmptyPath,
+    /// Consecutive nodes of the path are not joined by an edge.
+    MissingEdge { from: N, to: N },
+    /// The path costs something other than what was claimed.
+    WrongCost { claimed: D, actual: D },
+    /// There is a cheaper path between the same nodes.
+    NotShortest { claimed: D, shortest: D },
+}
+
+impl<N: fmt::Debug, D: fmt::Debug> fmt::Display for VerificationError<N, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::EmptyPath => write!(f, "path is empty"),
+            VerificationError::MissingEdge { from, to } => {
+                write!(f, "path uses a missing edge from {from:?} to {to:?}")
+            }
+            VerificationError::WrongCost { claimed, actual } => {
+                write!(
+                    f,
+                    "path was claimed to cost {claimed:?} but costs {actual:?}"
+                )
+            }
+            VerificationError::NotShortest { claimed, shortest } => {
+                write!(
+                    f,
+                    "path costs {claimed:?} but a path costing {shortest:?} exists"
+                )
+            }
+        }
+    }
+}
+
+impl<N: fmt::Debug, D: fmt::Debug> Error for VerificationError<N, D> {}
+
+/// Problems reading a graph with `AdjacencyListGraph::read_edges`. Lines are numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The line has neither one field, for a node, nor three, for an edge.
+    WrongFieldCount { line: usize, found: usize },
+    /// This text on the line is not a valid node.
+    InvalidNode { line: usize, text: String },
+    /// This text on the line is not a valid distance.
+    InvalidDist { line: usize, text: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::WrongFieldCount { line, found } => {
+                write!(
+                    f,
+                    "line {line}: expected a node or `from to dist`, found {found} fields"
+                )
+            }
+            ParseError::InvalidNode { line, text } => {
+                write!(f, "line {line}: {text:?} is not a valid node")
+            }
+            ParseError::InvalidDist { line, text } => {
+                write!(f, "line {line}: {text:?} is not a valid distance")
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
@@ -0,0 +1,205 @@
+use crate::VGraph;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// An edge as its `(from, to)` nodes.
+type Edge<N> = (N, N);
+
+/// The most flow that can be sent from `source` to `sink` when each edge carries at most
+/// `capacity(from, to)`.
+///
+/// Edmonds-Karp, repeatedly pushing flow along the path with the fewest edges that still has
+/// room. Every node reachable from `source` is visited, so that part of the graph must be
+/// finite. `source == sink` gives 0.
+pub fn max_flow<G, C>(g: G, source: G::Node, sink: G::Node, capacity: C) -> u64
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    C: Fn(G::Node, G::Node) -> u64,
+{
+    let mut network = Residual::new(&g, source, &capacity);
+    network.saturate(source, sink)
+}
+
+/// The smallest set of edges whose removal disconnects `sink` from `source`, weighed by
+/// `capacity`, along with its total capacity.
+///
+/// Runs `max_flow` and cuts every edge leading out of the nodes `source` can still reach in the
+/// residual graph, so the cut value always equals the maximum flow. Edges are listed as
+/// `(from, to)` in no particular order.
+pub fn min_cut<G, C>(g: G, source: G::Node, sink: G::Node, capacity: C) -> (u64, Vec<Edge<G::Node>>)
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    C: Fn(G::Node, G::Node) -> u64,
+{
+    let mut network = Residual::new(&g, source, &capacity);
+    let flow = network.saturate(source, sink);
+    if source == sink {
+        return (flow, Vec::new());
+    }
+
+    let source_side: HashSet<G::Node> = network.augmenting_prev(source).into_keys().collect();
+    let cut = network
+        .edges
+        .iter()
+        .copied()
+        .filter(|(from, to)| source_side.contains(from) && !source_side.contains(to))
+        .collect();
+    (flow, cut)
+}
+
+/// Remaining capacity of every edge reachable from the source, and of their reverses.
+struct Residual<N> {
+    // The original edges, each pair listed once.
+    edges: Vec<Edge<N>>,
+    // Nodes joined by an edge in either direction.
+    neighbors: HashMap<N, Vec<N>>,
+    room: HashMap<Edge<N>, u64>,
+}
+
+impl<N> Residual<N>
+where
+    N: Hash + Eq + Copy,
+{
+    fn new<G, C>(g: &G, source: N, capacity: &C) -> Self
+    where
+        G: VGraph<Node = N>,
+        C: Fn(N, N) -> u64,
+    {
+        let mut network = Residual {
+            edges: Vec::new(),
+            neighbors: HashMap::new(),
+            room: HashMap::new(),
+        };
+        let mut listed = HashSet::new();
+        let mut seen = HashSet::from([source]);
+        let mut to_visit = VecDeque::from([source]);
+        while let Some(cur) = to_visit.pop_front() {
+            for next in g.out_edges(cur) {
+                if seen.insert(next) {
+                    to_visit.push_back(next);
+                }
+                if next == cur {
+                    continue;
+                }
+                // Both directions get room at once, so this is the first edge between the two.
+                if !network.room.contains_key(&(cur, next)) {
+                    network.neighbors.entry(cur).or_default().push(next);
+                    network.neighbors.entry(next).or_default().push(cur);
+                }
+                if listed.insert((cur, next)) {
+                    network.edges.push((cur, next));
+                }
+                *network.room.entry((cur, next)).or_default() += capacity(cur, next);
+                network.room.entry((next, cur)).or_default();
+            }
+        }
+        network
+    }
+
+    /// Pushes flow from `source` to `sink` until no path has room left, returning the total.
+    fn saturate(&mut self, source: N, sink: N) -> u64 {
+        if source == sink {
+            return 0;
+        }
+        let mut flow = 0;
+        loop {
+            let prev = self.augmenting_prev(source);
+            if !prev.contains_key(&sink) {
+                return flow;
+            }
+            let mut path = Vec::new();
+            let mut cur = sink;
+            while cur != source {
+                let from = prev[&cur];
+                path.push((from, cur));
+                cur = from;
+            }
+            let bottleneck = path
+                .iter()
+                .map(|edge| self.room[edge])
+                .min()
+                .expect("source and sink differ, so the path has an edge.");
+            for (from, to) in path {
+                *self.room.get_mut(&(from, to)).expect("Path edges exist.") -= bottleneck;
+                *self
+                    .room
+                    .get_mut(&(to, from))
+                    .expect("Reverse edges exist.") += bottleneck;
+            }
+            flow += bottleneck;
+        }
+    }
+
+    /// Breadth first search over edges with room left, mapping each reached node to the node it
+    /// was reached from. `source` maps to itself.
+    fn augmenting_prev(&self, source: N) -> HashMap<N, N> {
+        let mut prev = HashMap::from([(source, source)]);
+        let mut to_visit = VecDeque::from([source]);
+        while let Some(cur) = to_visit.pop_front() {
+            for &next in self.neighbors.get(&cur).into_iter().flatten() {
+                if self.room[&(cur, next)] > 0 && !prev.contains_key(&next) {
+                    prev.insert(next, cur);
+                    to_visit.push_back(next);
+                }
+            }
+        }
+        prev
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AdjacencyListGraph;
+
+    /// The flow network from Introduction to Algorithms, with a maximum flow of 23.
+    fn network() -> AdjacencyListGraph<char, u64> {
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge('s', 'a', 16);
+        g.add_edge('s', 'c', 13);
+        g.add_edge('a', 'b', 12);
+        g.add_edge('b', 'c', 9);
+        g.add_edge('c', 'a', 4);
+        g.add_edge('c', 'd', 14);
+        g.add_edge('d', 'b', 7);
+        g.add_edge('b', 't', 20);
+        g.add_edge('d', 't', 4);
+        g
+    }
+
+    #[test]
+    fn cut_equals_max_flow() {
+        let g = network();
+        let capacity = |from, to| g.dist(from, to);
+        assert_eq!(23, max_flow(&g, 's', 't', capacity));
+
+        let (value, mut cut) = min_cut(&g, 's', 't', capacity);
+        cut.sort();
+        assert_eq!(23, value);
+        assert_eq!(vec![('a', 'b'), ('d', 'b'), ('d', 't')], cut);
+        assert_eq!(value, cut.iter().map(|&(a, b)| g.dist(a, b)).sum::<u64>());
+    }
+
+    #[test]
+    fn edges_both_ways() {
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(0, 1, 3);
+        g.add_edge(1, 0, 5);
+        g.add_edge(1, 2, 4);
+        g.add_edge(2, 1, 2);
+        let capacity = |from, to| g.dist(from, to);
+        assert_eq!((3, vec![(0, 1)]), min_cut(&g, 0, 2, capacity));
+        assert_eq!((2, vec![(2, 1)]), min_cut(&g, 2, 0, capacity));
+    }
+
+    #[test]
+    fn disconnected_and_trivial() {
+        let g = network();
+        let capacity = |from, to| g.dist(from, to);
+        assert_eq!((0, vec![]), min_cut(&g, 't', 's', capacity));
+        assert_eq!((0, vec![]), min_cut(&g, 's', 's', capacity));
+        assert_eq!(0, max_flow(&g, 's', 'x', capacity));
+    }
+}
@@ -0,0 +1,82 @@
+use crate::VGraph;
+use std::marker::PhantomData;
+
+/// A graph defined by a pair of closures, for quick searches without a dedicated type.
+///
+/// ```
+/// use vgraph::{breadth_first_search, FnGraph};
+///
+/// let line = FnGraph::new(|n: u32| vec![n + 1], |_, _| 1);
+/// assert_eq!(Some(vec![0, 1, 2]), breadth_first_search(line, 0, 2));
+/// ```
+pub struct FnGraph<N, D, E, W> {
+    out_edges: E,
+    dist: W,
+    // Ties the node and distance types to the closures.
+    _types: PhantomData<fn(N) -> D>,
+}
+
+impl<N, D, E, W> FnGraph<N, D, E, W>
+where
+    E: Fn(N) -> Vec<N>,
+    W: Fn(N, N) -> D,
+{
+    pub fn new(out_edges: E, dist: W) -> Self {
+        FnGraph {
+            out_edges,
+            dist,
+            _types: PhantomData,
+        }
+    }
+}
+
+impl<N, D, E, W> VGraph for FnGraph<N, D, E, W>
+where
+    E: Fn(N) -> Vec<N>,
+    W: Fn(N, N) -> D,
+{
+    type Node = N;
+
+    type Dist = D;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        (self.out_edges)(node)
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        (self.dist)(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{a_star_search, breadth_first_search};
+
+    #[test]
+    fn breadth_first_search_on_closures() {
+        let g = FnGraph::new(
+            |n: usize| match n {
+                1 => vec![2],
+                2 => vec![3],
+                _ => vec![],
+            },
+            |_, _| 1,
+        );
+        assert_eq!(Some(vec![1, 2, 3]), breadth_first_search(&g, 1, 3));
+        assert_eq!(None, breadth_first_search(&g, 3, 1));
+    }
+
+    #[test]
+    fn a_star_search_on_closures() {
+        // Counting up by 1 costs 1, doubling costs 2.
+        let g = FnGraph::new(
+            |n: u32| if n < 100 { vec![n + 1, n * 2] } else { vec![] },
+            |from: u32, to: u32| if to == from + 1 { 1 } else { 2 },
+        );
+        assert_eq!(
+            Some(vec![1, 2, 4, 8, 16, 17]),
+            a_star_search(g, 1, |n| n == 17, |_| 0u32)
+        );
+    }
+}
@@ -0,0 +1,428 @@
+use crate::{a_star_search, VGraph};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+type Cell = (usize, usize);
+
+/// A rectangular grid where each cell connects to its up, down, left and right neighbors.
+///
+/// Nodes are `(x, y)` coordinates and every move costs 1. Blocked cells have no edges in or
+/// out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridGraph {
+    width: usize,
+    height: usize,
+    blocked: HashSet<(usize, usize)>,
+}
+
+impl GridGraph {
+    pub fn new(width: usize, height: usize) -> Self {
+        GridGraph {
+            width,
+            height,
+            blocked: HashSet::new(),
+        }
+    }
+
+    /// Marks a cell as impassable.
+    pub fn block(&mut self, cell: (usize, usize)) {
+        self.blocked.insert(cell);
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// True if `cell` is inside the grid and not blocked.
+    pub fn is_open(&self, cell: (usize, usize)) -> bool {
+        cell.0 < self.width && cell.1 < self.height && !self.blocked.contains(&cell)
+    }
+
+    /// True if a straight line between the centers of `from` and `to` only crosses open cells.
+    ///
+    /// A line passing exactly through a corner needs both cells beside the corner to be open, so
+    /// it never squeezes diagonally between two blocked cells.
+    pub fn line_of_sight(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        let (mut x, mut y) = (from.0 as isize, from.1 as isize);
+        let (end_x, end_y) = (to.0 as isize, to.1 as isize);
+        let (step_x, step_y) = ((end_x - x).signum(), (end_y - y).signum());
+        let (dx, dy) = ((end_x - x).abs(), (end_y - y).abs());
+        let open = |x: isize, y: isize| self.is_open((x as usize, y as usize));
+
+        // Walks every cell the line passes through, `error` tracks which cell edge it crosses
+        // next.
+        let mut error = dx - dy;
+        while (x, y) != (end_x, end_y) {
+            if !open(x, y) {
+                return false;
+            }
+            match error.cmp(&0) {
+                Ordering::Greater => {
+                    x += step_x;
+                    error -= 2 * dy;
+                }
+                Ordering::Less => {
+                    y += step_y;
+                    error += 2 * dx;
+                }
+                Ordering::Equal => {
+                    if !open(x + step_x, y) || !open(x, y + step_y) {
+                        return false;
+                    }
+                    x += step_x;
+                    y += step_y;
+                    error += 2 * (dx - dy);
+                }
+            }
+        }
+        open(end_x, end_y)
+    }
+
+    /// Every open cell, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.height)
+            .flat_map(move |y| (0..self.width).map(move |x| (x, y)))
+            .filter(move |&cell| self.is_open(cell))
+    }
+}
+
+/// The way a move between neighboring cells goes. `y` grows downward, so `Up` lowers it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl GridGraph {
+    /// The direction of the move from `from` to `to`, or `None` if they are not neighbors.
+    pub fn direction(from: (usize, usize), to: (usize, usize)) -> Option<Direction> {
+        match GridMove::between(from, to)? {
+            GridMove::Straight(direction) => Some(direction),
+            GridMove::UpLeft | GridMove::UpRight | GridMove::DownLeft | GridMove::DownRight => None,
+        }
+    }
+}
+
+/// A move to one of the eight cells around a cell, for paths from graphs that also connect
+/// corners. `GridGraph` itself only moves straight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GridMove {
+    Straight(Direction),
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl GridMove {
+    /// The move from `from` to `to`, diagonals included, or `None` if they are not neighbors.
+    pub fn between(from: (usize, usize), to: (usize, usize)) -> Option<GridMove> {
+        match (
+            to.0 as isize - from.0 as isize,
+            to.1 as isize - from.1 as isize,
+        ) {
+            (0, -1) => Some(GridMove::Straight(Direction::Up)),
+            (0, 1) => Some(GridMove::Straight(Direction::Down)),
+            (-1, 0) => Some(GridMove::Straight(Direction::Left)),
+            (1, 0) => Some(GridMove::Straight(Direction::Right)),
+            (-1, -1) => Some(GridMove::UpLeft),
+            (1, -1) => Some(GridMove::UpRight),
+            (-1, 1) => Some(GridMove::DownLeft),
+            (1, 1) => Some(GridMove::DownRight),
+            _ => None,
+        }
+    }
+}
+
+/// Compresses a path into runs of moves in the same direction, such as 3 right then 2 down.
+///
+/// Diagonal moves are allowed. A path of at most one cell has no moves and gives an empty list.
+///
+/// Panics if two consecutive cells of the path are not neighbors.
+pub fn encode_grid_path(path: &[Cell]) -> Vec<(GridMove, usize)> {
+    let mut runs: Vec<(GridMove, usize)> = Vec::new();
+    for step in path.windows(2) {
+        let direction = GridMove::between(step[0], step[1]).unwrap_or_else(|| {
+            panic!(
+                "path moves from {:?} to {:?}, which are not neighbors",
+                step[0], step[1]
+            )
+        });
+        match runs.last_mut() {
+            Some((last, count)) if *last == direction => *count += 1,
+            _ => runs.push((direction, 1)),
+        }
+    }
+    runs
+}
+
+/// Shortest path from `start` to `end` on a grid, with the direction each cell was entered
+/// from. The start was not entered by a move, so its direction is `None`.
+pub fn a_star_grid_directions(
+    grid: &GridGraph,
+    start: Cell,
+    end: Cell,
+) -> Option<Vec<(Cell, Option<Direction>)>> {
+    let manhattan = |(x, y): Cell| x.abs_diff(end.0) + y.abs_diff(end.1);
+    let path = a_star_search(grid, start, |n| n == end, manhattan)?;
+    let moves = path
+        .windows(2)
+        .map(|step| (step[1], GridGraph::direction(step[0], step[1])));
+    Some(std::iter::once((start, None)).chain(moves).collect())
+}
+
+impl VGraph for GridGraph {
+    type Node = (usize, usize);
+
+    type Dist = usize;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        if !self.is_open(node) {
+            return vec![];
+        }
+
+        let (x, y) = node;
+        let mut neighbors = Vec::with_capacity(4);
+        if x > 0 {
+            neighbors.push((x - 1, y));
+        }
+        neighbors.push((x + 1, y));
+        if y > 0 {
+            neighbors.push((x, y - 1));
+        }
+        neighbors.push((x, y + 1));
+        neighbors.retain(|&cell| self.is_open(cell));
+        neighbors
+    }
+
+    fn dist(&self, _from: Self::Node, _to: Self::Node) -> Self::Dist {
+        1
+    }
+}
+
+/// A grid whose open cells are decided by a closure, for maps that are generated or streamed
+/// rather than stored.
+///
+/// Nodes are `(x, y)` coordinates with `0 <= x < width` and `0 <= y < height`, and each cell
+/// connects to its up, down, left and right neighbors at a cost of 1, like `GridGraph`.
+/// `passable` is only asked about cells inside the grid, when a search reaches one of their
+/// neighbors, so the grid can be far larger than what fits in memory.
+pub struct ProcGrid<P> {
+    width: i32,
+    height: i32,
+    passable: P,
+}
+
+impl<P> ProcGrid<P>
+where
+    P: Fn((i32, i32)) -> bool,
+{
+    pub fn new(width: i32, height: i32, passable: P) -> Self {
+        ProcGrid {
+            width,
+            height,
+            passable,
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// True if `cell` is inside the grid and `passable`.
+    pub fn is_open(&self, cell: (i32, i32)) -> bool {
+        (0..self.width).contains(&cell.0)
+            && (0..self.height).contains(&cell.1)
+            && (self.passable)(cell)
+    }
+}
+
+impl<P> VGraph for ProcGrid<P>
+where
+    P: Fn((i32, i32)) -> bool,
+{
+    type Node = (i32, i32);
+
+    type Dist = usize;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        if !self.is_open(node) {
+            return vec![];
+        }
+
+        let (x, y) = node;
+        let mut neighbors = vec![(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)];
+        neighbors.retain(|&cell| self.is_open(cell));
+        neighbors
+    }
+
+    fn dist(&self, _from: Self::Node, _to: Self::Node) -> Self::Dist {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{a_star_search, path_length};
+
+    #[test]
+    fn out_edges_stay_inside_the_grid() {
+        let mut g = GridGraph::new(3, 2);
+        g.block((1, 1));
+        assert_eq!(vec![(1, 0), (0, 1)], g.out_edges((0, 0)));
+        assert_eq!(vec![(1, 0), (2, 1)], g.out_edges((2, 0)));
+        assert!(g.out_edges((1, 1)).is_empty());
+        assert_eq!(5, g.cells().count());
+    }
+
+    #[test]
+    fn a_star_search_routes_around_blocks() {
+        let mut g = GridGraph::new(3, 3);
+        g.block((1, 0));
+        g.block((1, 1));
+        let path = a_star_search(&g, (0, 0), |n| n == (2, 0), |_| 0).unwrap();
+        assert_eq!(6, path_length(&g, path));
+    }
+
+    #[test]
+    fn short_search_on_a_huge_grid() {
+        // Reserving room for every cell would need terabytes.
+        let g = GridGraph::new(1_000_000, 1_000_000);
+        let path = a_star_search(&g, (5, 5), |n| n == (7, 6), |_| 0).unwrap();
+        assert_eq!(3, path_length(&g, path));
+    }
+
+    #[test]
+    fn directions_along_an_l() {
+        let mut g = GridGraph::new(3, 3);
+        g.block((0, 1));
+        g.block((1, 1));
+        g.block((1, 2));
+        assert_eq!(
+            Some(vec![
+                ((0, 0), None),
+                ((1, 0), Some(Direction::Right)),
+                ((2, 0), Some(Direction::Right)),
+                ((2, 1), Some(Direction::Down)),
+                ((2, 2), Some(Direction::Down)),
+            ]),
+            a_star_grid_directions(&g, (0, 0), (2, 2))
+        );
+        assert_eq!(
+            Some(vec![((1, 0), None)]),
+            a_star_grid_directions(&g, (1, 0), (1, 0))
+        );
+        assert_eq!(None, GridGraph::direction((0, 0), (1, 1)));
+        assert_eq!(Some(Direction::Up), GridGraph::direction((2, 1), (2, 0)));
+    }
+
+    #[test]
+    fn line_of_sight_through_blocks() {
+        let mut g = GridGraph::new(5, 5);
+        assert!(g.line_of_sight((0, 0), (4, 2)));
+        g.block((2, 1));
+        assert!(!g.line_of_sight((0, 0), (4, 2)));
+        assert!(g.line_of_sight((0, 0), (4, 0)));
+
+        // Touching the corner of a blocked cell is not allowed.
+        let mut g = GridGraph::new(5, 5);
+        assert!(g.line_of_sight((0, 0), (3, 3)));
+        g.block((1, 2));
+        assert!(!g.line_of_sight((0, 0), (3, 3)));
+    }
+
+    #[test]
+    fn encode_staircase() {
+        let staircase = [(0, 0), (1, 0), (1, 1), (2, 1), (2, 2), (3, 2), (3, 3)];
+        assert_eq!(
+            vec![
+                (GridMove::Straight(Direction::Right), 1),
+                (GridMove::Straight(Direction::Down), 1),
+                (GridMove::Straight(Direction::Right), 1),
+                (GridMove::Straight(Direction::Down), 1),
+                (GridMove::Straight(Direction::Right), 1),
+                (GridMove::Straight(Direction::Down), 1),
+            ],
+            encode_grid_path(&staircase)
+        );
+
+        let runs = [(0, 0), (1, 0), (2, 0), (3, 0), (3, 1), (3, 2), (4, 2)];
+        assert_eq!(
+            vec![
+                (GridMove::Straight(Direction::Right), 3),
+                (GridMove::Straight(Direction::Down), 2),
+                (GridMove::Straight(Direction::Right), 1)
+            ],
+            encode_grid_path(&runs)
+        );
+
+        let diagonal = [(2, 2), (1, 1), (0, 0), (1, 1), (2, 0)];
+        assert_eq!(
+            vec![
+                (GridMove::UpLeft, 2),
+                (GridMove::DownRight, 1),
+                (GridMove::UpRight, 1)
+            ],
+            encode_grid_path(&diagonal)
+        );
+        assert!(encode_grid_path(&[(4, 4)]).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "not neighbors")]
+    fn encode_rejects_jumps() {
+        encode_grid_path(&[(0, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn proc_grid_around_pillars() {
+        // Every other cell on every other row is a pillar.
+        let pillars = |(x, y): (i32, i32)| x % 2 == 0 || y % 2 == 0;
+        let end = (6, 6);
+        let manhattan =
+            |(x, y): (i32, i32)| x.abs_diff(end.0) as usize + y.abs_diff(end.1) as usize;
+        let g = ProcGrid::new(7, 7, pillars);
+        assert_eq!(vec![(0, 0), (2, 0)], g.out_edges((1, 0)));
+        assert!(g.out_edges((1, 1)).is_empty());
+        let path = a_star_search(&g, (0, 0), |n| n == end, manhattan).unwrap();
+        assert_eq!(12, path_length(&g, path.clone()));
+        assert!(path.iter().all(|&cell| pillars(cell)));
+
+        // Asking about a cell outside the grid would panic.
+        let g = ProcGrid::new(7, 7, |(x, y): (i32, i32)| {
+            assert!((0..7).contains(&x) && (0..7).contains(&y));
+            pillars((x, y))
+        });
+        assert_eq!(None, a_star_search(&g, (0, 0), |n| n == (-1, 0), |_| 0));
+    }
+
+    #[test]
+    fn proc_grid_checkerboard_is_walled_in() {
+        let checkerboard = |(x, y): (i32, i32)| (x + y) % 2 == 0;
+        let g = ProcGrid::new(8, 8, checkerboard);
+        assert!(g.out_edges((2, 2)).is_empty());
+        assert_eq!(None, a_star_search(&g, (0, 0), |n| n == (7, 7), |_| 0));
+    }
+
+    #[test]
+    fn proc_grid_larger_than_memory() {
+        // Pillars again, spread over far too many cells to store.
+        let g = ProcGrid::new(i32::MAX, i32::MAX, |(x, y)| x % 2 == 0 || y % 2 == 0);
+        let end = (1000, 1000);
+        let manhattan =
+            |(x, y): (i32, i32)| x.abs_diff(end.0) as usize + y.abs_diff(end.1) as usize;
+        let path = a_star_search(&g, (0, 0), |n| n == end, manhattan).unwrap();
+        assert_eq!(2000, path_length(&g, path));
+    }
+}
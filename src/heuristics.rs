@@ -0,0 +1,53 @@
+use num::traits::Zero;
+
+/// A heuristic estimating the largest of `heuristics` for every node.
+///
+/// If each one never overestimates, neither does their maximum, and it is at least as close to
+/// the true distance as the best of them on every node. Useful for combining estimates that are
+/// each accurate in different places, such as several pattern databases. With no heuristics
+/// the estimate is always zero.
+pub fn max_heuristic<'a, N, D>(heuristics: Vec<Box<dyn Fn(N) -> D + 'a>>) -> impl Fn(N) -> D + 'a
+where
+    N: Copy + 'a,
+    D: Zero + Ord + 'a,
+{
+    move |node| {
+        heuristics
+            .iter()
+            .map(|h| h(node))
+            .fold(D::zero(), |best, estimate| best.max(estimate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{a_star_search, dijkstra_iter, path_length, GridGraph};
+    use std::collections::HashMap;
+
+    #[test]
+    fn max_of_admissible_estimates() {
+        let mut g = GridGraph::new(8, 8);
+        for y in 1..8 {
+            g.block((4, y));
+        }
+        let end = (7, 7);
+        // Moves are symmetric, so distances from the end are distances to it.
+        let to_end: HashMap<(usize, usize), usize> = dijkstra_iter(&g, end).collect();
+        let across = |(x, _): (usize, usize)| x.abs_diff(end.0);
+        let down = |(_, y): (usize, usize)| y.abs_diff(end.1);
+        let combined = max_heuristic(vec![Box::new(across), Box::new(down)]);
+
+        for (&cell, &dist) in &to_end {
+            assert!(combined(cell) >= across(cell));
+            assert!(combined(cell) >= down(cell));
+            assert!(combined(cell) <= dist, "{cell:?}");
+        }
+        assert_eq!(7, combined((0, 7)));
+        assert_eq!(6, combined((5, 1)));
+
+        let path = a_star_search(&g, (0, 7), |n| n == end, &combined).unwrap();
+        assert_eq!(to_end[&(0, 7)], path_length(&g, path));
+        assert_eq!(0, max_heuristic::<u32, u32>(vec![])(5));
+    }
+}
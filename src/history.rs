@@ -0,0 +1,134 @@
+use num::traits::Zero;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::Add;
+
+/// Virtual graph whose edge costs depend on the whole path taken so far, such as fuel or
+/// fatigue.
+pub trait HistoryVGraph {
+    type Node;
+
+    // Measure of the distance between nodes
+    type Dist;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node>;
+
+    /// Cost of stepping to `next` after walking `path`. `path` is never empty and ends at the
+    /// node `next` is reached from.
+    fn dist_along(&self, path: &[Self::Node], next: Self::Node) -> Self::Dist;
+}
+
+/// Finds the cheapest path from `start` to a node matching `is_end` on a history aware graph.
+///
+/// Since the same node can cost differently depending on how it was reached, nodes can not be
+/// closed once visited. Every path prefix is its own search state, so this explores far more
+/// states than `a_star_search` and will not terminate on a cyclic graph where no end is
+/// reachable.
+pub fn history_search<G, F>(g: G, start: G::Node, is_end: F) -> Option<Vec<G::Node>>
+where
+    G: HistoryVGraph,
+    G::Node: Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+{
+    let mut to_explore = BinaryHeap::new();
+    to_explore.push((Reverse(G::Dist::zero()), vec![start]));
+    while let Some((Reverse(cost), path)) = to_explore.pop() {
+        let cur = *path
+            .last()
+            .expect("All explore paths must have at least 1 node.");
+        if is_end(cur) {
+            return Some(path);
+        }
+
+        for next in g.out_edges(cur) {
+            let next_cost = cost + g.dist_along(&path, next);
+            let mut next_path = path.clone();
+            next_path.push(next);
+            to_explore.push((Reverse(next_cost), next_path));
+        }
+    }
+
+    None
+}
+
+/// Total cost of walking `path` on a history aware graph.
+pub fn history_path_length<G>(g: G, path: &[G::Node]) -> G::Dist
+where
+    G: HistoryVGraph,
+    G::Node: Copy,
+    G::Dist: Zero + Add<Output = G::Dist>,
+{
+    let mut distance = G::Dist::zero();
+    for i in 1..path.len() {
+        distance = distance + g.dist_along(&path[..i], path[i]);
+    }
+
+    distance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every step costs 1, plus 10 for entering a node that was already visited.
+    /// Node 2 is a depot, after passing it every step is free.
+    struct Revisits {}
+
+    impl HistoryVGraph for Revisits {
+        type Node = usize;
+
+        type Dist = usize;
+
+        fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+            match node {
+                1 => vec![2, 3],
+                2 => vec![1, 3],
+                3 => vec![1, 2, 4],
+                _ => vec![],
+            }
+        }
+
+        fn dist_along(&self, path: &[Self::Node], next: Self::Node) -> Self::Dist {
+            let revisit = if path.contains(&next) { 10 } else { 0 };
+            let step = if path.contains(&2) { 0 } else { 1 };
+            step + revisit
+        }
+    }
+
+    #[test]
+    fn history_path_length_charges_revisits() {
+        assert_eq!(2, history_path_length(Revisits {}, &[1, 3, 4]));
+        assert_eq!(24, history_path_length(Revisits {}, &[1, 3, 1, 3, 4]));
+        assert_eq!(1, history_path_length(Revisits {}, &[1, 2, 3, 4]));
+        assert_eq!(11, history_path_length(Revisits {}, &[1, 2, 1, 3, 4]));
+    }
+
+    #[test]
+    fn history_search_avoids_revisits() {
+        assert_eq!(
+            Some(vec![1, 2, 3, 4]),
+            history_search(Revisits {}, 1, |n| n == 4)
+        );
+    }
+
+    #[test]
+    fn history_search_for_non_path_terminates() {
+        struct Line {}
+        impl HistoryVGraph for Line {
+            type Node = usize;
+            type Dist = usize;
+            fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+                if node < 5 {
+                    vec![node + 1]
+                } else {
+                    vec![]
+                }
+            }
+            fn dist_along(&self, path: &[Self::Node], _next: Self::Node) -> Self::Dist {
+                path.len()
+            }
+        }
+        assert_eq!(None, history_search(Line {}, 1, |n| n == 33));
+    }
+}
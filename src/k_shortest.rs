@@ -0,0 +1,381 @@
+use crate::{
+    a_star_search, dijkstra_iter, DijkstraIter, PredecessorPath, Reversed, ReversibleVGraph, VGraph,
+};
+use num::traits::Zero;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+use std::hash::Hash;
+
+/// Iterates over the simple paths from `start` to `end` in order of increasing cost, using
+/// Yen's algorithm.
+///
+/// Each path is found with a fresh search, so taking many paths is expensive. Paths of equal
+/// cost come out in lexicographic order.
+pub fn shortest_simple_paths<G>(g: G, start: G::Node, end: G::Node) -> ShortestSimplePaths<G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    ShortestSimplePaths {
+        g,
+        start,
+        end,
+        found: Vec::new(),
+        candidates: BinaryHeap::new(),
+        seen: HashSet::new(),
+    }
+}
+
+/// Up to `k` simple paths from `start` to `end`, cheapest first.
+pub fn k_shortest_paths<G>(g: G, start: G::Node, end: G::Node, k: usize) -> Vec<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    shortest_simple_paths(g, start, end).take(k).collect()
+}
+
+/// The cheapest simple path from `start` to `end` other than the shortest one, or `None` if
+/// there is at most one.
+///
+/// Any second path follows the shortest one up to some node and then leaves it along another
+/// edge, so it costs at least the shortest path there, plus that edge, plus the distance left
+/// from where the edge leads. One Dijkstra search backward from `end` gives every such distance
+/// and the shortest path tree to reach `end` along, so the best deviation is found by scanning
+/// the edges leaving the shortest path. Only when the cheapest deviation would come back through
+/// a node already used does this fall back to Yen's algorithm, as `shortest_simple_paths`.
+pub fn second_shortest_path<G>(g: G, start: G::Node, end: G::Node) -> Option<Vec<G::Node>>
+where
+    G: ReversibleVGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let mut settle = dijkstra_iter(Reversed { g: &g }, end);
+    settle.by_ref().for_each(drop);
+    // Reversed, the previous node of each node is the next one on its way to `end`.
+    let DijkstraIter {
+        settled: to_end,
+        prev: toward_end,
+        ..
+    } = settle;
+    to_end.get(&start)?;
+    let shortest: Vec<G::Node> = PredecessorPath::new(&toward_end, start).collect();
+
+    // The cheapest deviation, and the cheapest one that stays simple, as its cost and path.
+    let mut cheapest: Option<G::Dist> = None;
+    let mut best: Option<(G::Dist, Vec<G::Node>)> = None;
+    let mut root = HashSet::new();
+    let mut root_cost = G::Dist::zero();
+    for (i, step) in shortest.windows(2).enumerate() {
+        let (cur, on_path) = (step[0], step[1]);
+        root.insert(cur);
+        for next in g.out_edges(cur) {
+            if next == on_path || root.contains(&next) {
+                continue;
+            }
+            let Some(&rest) = to_end.get(&next) else {
+                continue;
+            };
+            let cost = root_cost + g.dist(cur, next) + rest;
+            cheapest = Some(cheapest.map_or(cost, |c| c.min(cost)));
+            if best.as_ref().is_some_and(|(b, _)| *b <= cost) {
+                continue;
+            }
+            let rest: Vec<G::Node> = PredecessorPath::new(&toward_end, next).collect();
+            if rest.iter().all(|node| !root.contains(node)) {
+                best = Some((cost, shortest[..=i].iter().copied().chain(rest).collect()));
+            }
+        }
+        root_cost = root_cost + g.dist(cur, on_path);
+    }
+
+    match best {
+        Some((cost, path)) if Some(cost) == cheapest => Some(path),
+        _ if cheapest.is_none() => None,
+        _ => shortest_simple_paths(&g, start, end).nth(1),
+    }
+}
+
+/// Up to `k` short paths from `start` to `end` that are mutually dissimilar.
+///
+/// Paths are considered cheapest first, and a path is kept only if its `edge_overlap` with
+/// every path kept so far is at most `max_overlap`. A `max_overlap` of 0 asks for edge disjoint
+/// paths, 1 accepts anything and matches `k_shortest_paths`. When fewer than `k` diverse paths
+/// exist, every simple path gets considered, which can take a long time on large graphs.
+pub fn k_diverse_paths<G>(
+    g: G,
+    start: G::Node,
+    end: G::Node,
+    k: usize,
+    max_overlap: f64,
+) -> Vec<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let mut diverse: Vec<Vec<G::Node>> = Vec::new();
+    for path in shortest_simple_paths(g, start, end) {
+        if diverse.len() >= k {
+            break;
+        }
+        if diverse
+            .iter()
+            .all(|kept| edge_overlap(kept, &path) <= max_overlap)
+        {
+            diverse.push(path);
+        }
+    }
+
+    diverse
+}
+
+/// The fraction of edges two paths share, relative to the path with fewer edges.
+///
+/// Returns 0 when either path has no edges.
+pub fn edge_overlap<N>(a: &[N], b: &[N]) -> f64
+where
+    N: Hash + Eq + Copy,
+{
+    let a_edges: HashSet<(N, N)> = a.windows(2).map(|w| (w[0], w[1])).collect();
+    let b_edges: HashSet<(N, N)> = b.windows(2).map(|w| (w[0], w[1])).collect();
+    let fewest = a_edges.len().min(b_edges.len());
+    if fewest == 0 {
+        return 0.0;
+    }
+    a_edges.intersection(&b_edges).count() as f64 / fewest as f64
+}
+
+/// A path and its cost, ordered so the cheapest path is the greatest.
+type Candidate<N, D> = (Reverse<D>, Reverse<Vec<N>>);
+
+/// Iterator returned by `shortest_simple_paths`.
+pub struct ShortestSimplePaths<G>
+where
+    G: VGraph,
+{
+    g: G,
+    start: G::Node,
+    end: G::Node,
+    // Paths already yielded, cheapest first.
+    found: Vec<Vec<G::Node>>,
+    // Paths that deviate from a found path somewhere, waiting to be yielded.
+    candidates: BinaryHeap<Candidate<G::Node, G::Dist>>,
+    seen: HashSet<Vec<G::Node>>,
+}
+
+impl<G> Iterator for ShortestSimplePaths<G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    type Item = Vec<G::Node>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = match self.found.last() {
+            None => {
+                let end = self.end;
+                a_star_search(&self.g, self.start, |n| n == end, |_| G::Dist::zero())?
+            }
+            Some(last) => {
+                let last = last.clone();
+                self.add_deviations(&last);
+                let (_, Reverse(path)) = self.candidates.pop()?;
+                path
+            }
+        };
+        self.seen.insert(next.clone());
+        self.found.push(next.clone());
+        Some(next)
+    }
+}
+
+impl<G> ShortestSimplePaths<G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    /// Adds every path that follows `last` up to some node and then leaves it.
+    fn add_deviations(&mut self, last: &[G::Node]) {
+        for i in 0..last.len().saturating_sub(1) {
+            let spur = last[i];
+            let root = &last[..=i];
+
+            // Block the next step of every found path sharing this root, so the spur leaves it.
+            let removed_edges: HashSet<(G::Node, G::Node)> = self
+                .found
+                .iter()
+                .filter(|path| path.len() > i + 1 && &path[..=i] == root)
+                .map(|path| (path[i], path[i + 1]))
+                .collect();
+            // The root's nodes can not be reused, or the path would not be simple.
+            let removed_nodes: HashSet<G::Node> = root[..i].iter().copied().collect();
+
+            let restricted = Restricted {
+                g: &self.g,
+                removed_nodes: &removed_nodes,
+                removed_edges: &removed_edges,
+            };
+            let end = self.end;
+            let Some(spur_path) =
+                a_star_search(restricted, spur, |n| n == end, |_| G::Dist::zero())
+            else {
+                continue;
+            };
+
+            let mut path = root[..i].to_vec();
+            path.extend(spur_path);
+            if self.seen.insert(path.clone()) {
+                let cost = path
+                    .windows(2)
+                    .fold(G::Dist::zero(), |total, w| total + self.g.dist(w[0], w[1]));
+                self.candidates.push((Reverse(cost), Reverse(path)));
+            }
+        }
+    }
+}
+
+/// A graph with some nodes and edges hidden.
+struct Restricted<'a, G>
+where
+    G: VGraph,
+{
+    g: G,
+    removed_nodes: &'a HashSet<G::Node>,
+    removed_edges: &'a HashSet<(G::Node, G::Node)>,
+}
+
+impl<G> VGraph for Restricted<'_, G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    type Node = G::Node;
+
+    type Dist = G::Dist;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        let mut neighbors = self.g.out_edges(node);
+        neighbors.retain(|next| {
+            !self.removed_nodes.contains(next) && !self.removed_edges.contains(&(node, *next))
+        });
+        neighbors
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        self.g.dist(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::random_graph;
+    use crate::test_graphs::Cycles;
+    use crate::{is_valid_path, path_length, AdjacencyListGraph};
+
+    /// Two cheap routes sharing their first edge, and an expensive separate route.
+    fn routes() -> AdjacencyListGraph<usize, u32> {
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(0, 1, 1);
+        g.add_edge(1, 2, 1);
+        g.add_edge(2, 9, 1);
+        g.add_edge(1, 3, 1);
+        g.add_edge(3, 9, 2);
+        g.add_edge(0, 4, 3);
+        g.add_edge(4, 5, 3);
+        g.add_edge(5, 9, 3);
+        g
+    }
+
+    #[test]
+    fn k_shortest_paths_in_cost_order() {
+        let g = routes();
+        assert_eq!(
+            vec![vec![0, 1, 2, 9], vec![0, 1, 3, 9], vec![0, 4, 5, 9]],
+            k_shortest_paths(&g, 0, 9, 5)
+        );
+    }
+
+    #[test]
+    fn k_shortest_paths_stops_when_paths_run_out() {
+        let paths = k_shortest_paths(Cycles {}, 1, 10, 3);
+        assert_eq!(vec![vec![1, 3, 4, 10], vec![1, 2, 3, 4, 10]], paths);
+        assert_eq!(5, path_length(Cycles {}, paths[0].clone()));
+        assert_eq!(6, path_length(Cycles {}, paths[1].clone()));
+    }
+
+    #[test]
+    fn second_shortest_on_cycles() {
+        let second = second_shortest_path(Cycles {}, 1, 10).unwrap();
+        assert_eq!(vec![1, 2, 3, 4, 10], second);
+        assert_ne!(
+            a_star_search(Cycles {}, 1, |n| n == 10, |_| 0).unwrap(),
+            second
+        );
+        assert_eq!(None, second_shortest_path(Cycles {}, 7, 9));
+        assert_eq!(None, second_shortest_path(Cycles {}, 4, 4));
+    }
+
+    #[test]
+    fn second_shortest_avoids_looping_back() {
+        // Leaving at 0 for 2 is cheapest by going back through 0, which is not simple.
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(0, 1, 1);
+        g.add_edge(1, 3, 1);
+        g.add_edge(0, 2, 1);
+        g.add_edge(2, 0, 1);
+        g.add_edge(2, 3, 5);
+        assert_eq!(Some(vec![0, 2, 3]), second_shortest_path(&g, 0, 3));
+    }
+
+    #[test]
+    fn second_shortest_costs_the_same_as_yen() {
+        let mut found = 0;
+        for seed in 0..10 {
+            let g = random_graph(30, 3, seed);
+            for end in [1, 17] {
+                let yen = shortest_simple_paths(&g, 0, end).nth(1);
+                let second = second_shortest_path(&g, 0, end);
+                assert_eq!(
+                    yen.map(|path| path_length(&g, path)),
+                    second.clone().map(|path| path_length(&g, path)),
+                    "seed {seed}"
+                );
+                if let Some(second) = second {
+                    assert!(is_valid_path(&g, &second));
+                    assert_eq!(second.len(), second.iter().collect::<HashSet<_>>().len());
+                    found += 1;
+                }
+            }
+        }
+        assert!(found > 10);
+    }
+
+    #[test]
+    fn k_diverse_paths_are_disjoint() {
+        let g = routes();
+        let paths = k_diverse_paths(&g, 0, 9, 2, 0.0);
+        assert_eq!(vec![vec![0, 1, 2, 9], vec![0, 4, 5, 9]], paths);
+        assert_eq!(0.0, edge_overlap(&paths[0], &paths[1]));
+    }
+
+    #[test]
+    fn k_diverse_paths_with_full_overlap_allowed() {
+        let g = routes();
+        assert_eq!(
+            k_shortest_paths(&g, 0, 9, 2),
+            k_diverse_paths(&g, 0, 9, 2, 1.0)
+        );
+    }
+
+    #[test]
+    fn edge_overlap_fraction() {
+        assert_eq!(1.0 / 3.0, edge_overlap(&[0, 1, 2, 9], &[0, 1, 3, 9]));
+        assert_eq!(0.0, edge_overlap(&[0], &[0, 1]));
+    }
+}
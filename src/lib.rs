@@ -1,9 +1,121 @@
-use num::traits::Zero;
+use num::traits::{One, Zero};
 use priority_queue::PriorityQueue;
-use std::cmp::Reverse;
-use std::collections::{HashMap, VecDeque};
+use std::cell::{Cell, RefCell};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::Hash;
-use std::ops::AddAssign;
+use std::ops::{Add, AddAssign, ControlFlow, Mul};
+use std::time::Instant;
+
+mod adjacency;
+pub mod bench;
+mod betweenness;
+mod bidirectional;
+mod center;
+mod cloned;
+mod closed;
+mod coloring;
+mod consistency;
+mod context;
+mod contraction;
+mod convert;
+mod csr;
+mod cuts;
+mod dag;
+mod dedup;
+mod degree;
+mod disjoint;
+mod dynamic_graph;
+mod dynamic_weight;
+mod error;
+mod flow;
+mod fn_graph;
+mod grid;
+mod heuristics;
+mod history;
+mod k_shortest;
+mod matching;
+mod memo;
+mod mst;
+mod node_cost;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod pareto;
+mod partial;
+mod path_options;
+mod pattern;
+#[cfg(feature = "petgraph")]
+mod petgraph_support;
+mod product;
+#[cfg(test)]
+mod properties;
+mod reach;
+mod recorder;
+mod shortest_dag;
+mod steiner;
+mod symmetric;
+#[cfg(test)]
+mod test_graphs;
+mod theta;
+mod turn;
+mod validate;
+mod verify;
+mod warm;
+mod zero_one;
+
+pub use adjacency::{snapshot, AdjacencyListGraph};
+pub use betweenness::{edge_betweenness_centrality, girvan_newman};
+pub use bidirectional::bidirectional_a_star;
+pub use center::{diameter_path, eccentricity, graph_center};
+pub use cloned::a_star_search_cloned;
+pub use closed::{BitSetClosed, ClosedSet};
+pub use coloring::{greedy_coloring, is_bipartite};
+pub use consistency::ConsistencyChecker;
+pub use context::{SearchContext, SearchStatus};
+pub use contraction::ContractionHierarchy;
+pub use convert::{ceil_heuristic, ceil_to_int};
+pub use csr::CsrGraph;
+pub use cuts::{articulation_points, bridges};
+pub use dag::{longest_path_dag, transitive_reduction};
+pub use dedup::DedupEdges;
+pub use degree::{degree_stats, degree_stats_reversible, DegreeStats};
+pub use disjoint::edge_disjoint_paths;
+pub use dynamic_graph::{a_star_dynamic, DynamicVGraph};
+pub use dynamic_weight::a_star_dynamic_weight;
+pub use error::{ParseError, SearchError, VerificationError};
+pub use flow::{max_flow, min_cut};
+pub use fn_graph::FnGraph;
+pub use grid::{
+    a_star_grid_directions, encode_grid_path, Direction, GridGraph, GridMove, ProcGrid,
+};
+pub use heuristics::max_heuristic;
+pub use history::{history_path_length, history_search, HistoryVGraph};
+pub use k_shortest::{
+    edge_overlap, k_diverse_paths, k_shortest_paths, second_shortest_path, shortest_simple_paths,
+    ShortestSimplePaths,
+};
+pub use matching::bipartite_matching;
+pub use memo::{a_star_cached_goal, NodeMemo};
+pub use mst::{min_arborescence, minimum_spanning_tree};
+pub use node_cost::{a_star_node_costs, NodeCostGraph};
+#[cfg(feature = "parallel")]
+pub use parallel::{batch_shortest_paths, delta_stepping};
+pub use pareto::{pareto_paths, MultiCriteriaVGraph, Objectives};
+pub use partial::a_star_partial;
+pub use path_options::{PathOptions, PathOutput};
+pub use pattern::PatternDatabase;
+pub use product::ProductGraph;
+pub use reach::{nodes_at_hop, reachability_bitset, reachable_set, transitive_closure, Bfs};
+pub use recorder::{ExpansionRecorder, GraphCall};
+pub use shortest_dag::{count_shortest_paths, lex_smallest_shortest_path, shortest_path_dag};
+pub use steiner::steiner_tree_approx;
+pub use symmetric::SymmetricWrapper;
+pub use theta::theta_star;
+pub use turn::TurnPenaltyGraph;
+pub use validate::{validate, validate_undirected, GraphWarning};
+pub use verify::verify_shortest_path;
+pub use warm::a_star_warm;
+pub use zero_one::zero_one_bfs;
 
 /// Virtual Graph.
 pub trait VGraph {
@@ -15,6 +127,94 @@ pub trait VGraph {
     fn out_edges(&self, node: Self::Node) -> Vec<Self::Node>;
 
     fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist;
+
+    /// True if `node` has no out edges.
+    ///
+    /// The default builds the neighbor list to find out. Graphs that can tell more cheaply
+    /// should override it, which lets `a_star_skip_terminals` avoid expanding dead ends.
+    fn is_terminal(&self, node: Self::Node) -> bool {
+        self.out_edges(node).is_empty()
+    }
+
+    /// Roughly how many nodes a search may visit, if known.
+    ///
+    /// Searches reserve room for this many nodes up front, up to a fixed cap, instead of growing
+    /// their maps as they go. Only a hint, a wrong value costs memory or time but never changes a
+    /// result. Graphs that are mostly empty space, such as a large grid, should leave it `None`.
+    fn size_hint(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// A graph that can also list the edges leading into a node, for searching backward.
+pub trait ReversibleVGraph: VGraph {
+    /// Every node with an edge to `node`. Must agree with `out_edges`.
+    fn in_edges(&self, node: Self::Node) -> Vec<Self::Node>;
+}
+
+/// A graph whose edges carry extra data, such as the move that was made to follow them.
+///
+/// Kept separate from `VGraph` so graphs without edge data do not need to name a type for it.
+pub trait LabeledVGraph: VGraph {
+    type EdgeData;
+
+    /// Same edges as `out_edges`, each paired with its data.
+    fn out_edges_with_data(&self, node: Self::Node) -> Vec<(Self::Node, Self::EdgeData)>;
+}
+
+/// Marker for graphs whose edges all go both ways.
+///
+/// Implementing this promises that whenever `b` is in `out_edges(a)`, `a` is in `out_edges(b)`
+/// and `dist(a, b) == dist(b, a)`. Algorithms that treat edges as undirected require it.
+/// `SymmetricWrapper` turns any `ReversibleVGraph` into one.
+pub trait UndirectedVGraph: VGraph {}
+
+/// Lets a graph be searched by reference, so it can still be inspected after the search.
+impl<G> VGraph for &G
+where
+    G: VGraph + ?Sized,
+{
+    type Node = G::Node;
+
+    type Dist = G::Dist;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        (**self).out_edges(node)
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        (**self).dist(from, to)
+    }
+
+    fn is_terminal(&self, node: Self::Node) -> bool {
+        (**self).is_terminal(node)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        (**self).size_hint()
+    }
+}
+
+impl<G> ReversibleVGraph for &G
+where
+    G: ReversibleVGraph + ?Sized,
+{
+    fn in_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        (**self).in_edges(node)
+    }
+}
+
+impl<G> UndirectedVGraph for &G where G: UndirectedVGraph + ?Sized {}
+
+impl<G> LabeledVGraph for &G
+where
+    G: LabeledVGraph + ?Sized,
+{
+    type EdgeData = G::EdgeData;
+
+    fn out_edges_with_data(&self, node: Self::Node) -> Vec<(Self::Node, Self::EdgeData)> {
+        (**self).out_edges_with_data(node)
+    }
 }
 
 pub fn breadth_first_search<G>(g: G, start: G::Node, end: G::Node) -> Option<Vec<G::Node>>
@@ -22,20 +222,159 @@ where
     G: VGraph,
     G::Node: Eq + Hash + Copy,
 {
-    let mut to_explore = VecDeque::new();
-    to_explore.push_back(start);
-    let mut prev = HashMap::<G::Node, G::Node>::new();
+    bfs_ordered(g, start, end, |_| {}, &mut HashSet::new())
+}
+
+/// Breadth first search that records the nodes it has reached in `closed`, which should start
+/// out empty.
+///
+/// Lets large searches use a smaller set than a `HashSet`, such as `BitSetClosed` for nodes
+/// numbered from zero.
+pub fn breadth_first_search_with<G, C>(
+    g: G,
+    start: G::Node,
+    end: G::Node,
+    closed: &mut C,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Eq + Hash + Copy,
+    C: ClosedSet<G::Node>,
+{
+    bfs_ordered(g, start, end, |_| {}, closed)
+}
+
+/// Breadth first search that visits each node's neighbors in sorted order.
+///
+/// Makes the returned path reproducible even when `out_edges` returns its neighbors in an
+/// arbitrary order, such as from a `HashSet`.
+pub fn breadth_first_search_sorted<G>(g: G, start: G::Node, end: G::Node) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Eq + Hash + Ord + Copy,
+{
+    breadth_first_search_sorted_by(g, start, end, Ord::cmp)
+}
+
+/// Breadth first search that sorts each node's neighbors with `compare` before visiting them.
+pub fn breadth_first_search_sorted_by<G, C>(
+    g: G,
+    start: G::Node,
+    end: G::Node,
+    mut compare: C,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Eq + Hash + Copy,
+    C: FnMut(&G::Node, &G::Node) -> Ordering,
+{
+    bfs_ordered(
+        g,
+        start,
+        end,
+        |nodes| nodes.sort_by(&mut compare),
+        &mut HashSet::new(),
+    )
+}
 
-    while let Some(cur) = to_explore.pop_front() {
+/// Shared breadth first search, `order` may rearrange the neighbors before they are queued.
+fn bfs_ordered<G, O, C>(
+    g: G,
+    start: G::Node,
+    end: G::Node,
+    order: O,
+    closed: &mut C,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Eq + Hash + Copy,
+    O: FnMut(&mut Vec<G::Node>),
+    C: ClosedSet<G::Node>,
+{
+    let mut prev = HashMap::<G::Node, G::Node>::new();
+    explore_ordered(g, start, &mut prev, order, closed, |prev, cur, from| {
+        // A closed set that forgets nodes lets them be reached again, keep the first way in so
+        // the previous nodes stay a tree.
+        if let Some(from) = from {
+            if cur != start {
+                prev.entry(cur).or_insert(from);
+            }
+        }
         if cur == end {
-            return Some(back_track(&prev, end));
+            ControlFlow::Break(back_track(prev, end))
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+}
+
+/// Visits every node reachable from `start` once, in breadth first order.
+///
+/// This drives the breadth first searches and reachability helpers. Searches that order their
+/// frontier by distance, such as `dijkstra_iter` and `a_star_search`, keep their own priority
+/// queue loops.
+///
+/// `step` is called for each node along with the node it was first reached from, `None` for
+/// `start`. Returning `ControlFlow::Break` stops the traversal and hands the value back.
+/// `state` is passed to every call of `step`, for anything the traversal should build up.
+pub fn explore<G, S, R, F>(g: G, start: G::Node, state: &mut S, step: F) -> Option<R>
+where
+    G: VGraph,
+    G::Node: Eq + Hash + Copy,
+    F: FnMut(&mut S, G::Node, Option<G::Node>) -> ControlFlow<R>,
+{
+    explore_ordered(g, start, state, |_| {}, &mut HashSet::new(), step)
+}
+
+/// `explore` that records the nodes it has reached in `closed`, which should start out empty.
+pub fn explore_with<G, S, R, C, F>(
+    g: G,
+    start: G::Node,
+    state: &mut S,
+    closed: &mut C,
+    step: F,
+) -> Option<R>
+where
+    G: VGraph,
+    G::Node: Eq + Hash + Copy,
+    C: ClosedSet<G::Node>,
+    F: FnMut(&mut S, G::Node, Option<G::Node>) -> ControlFlow<R>,
+{
+    explore_ordered(g, start, state, |_| {}, closed, step)
+}
+
+/// `explore`, where `order` may rearrange the neighbors before they are queued and reached
+/// nodes are kept in `seen`.
+fn explore_ordered<G, S, R, O, C, F>(
+    g: G,
+    start: G::Node,
+    state: &mut S,
+    mut order: O,
+    seen: &mut C,
+    mut step: F,
+) -> Option<R>
+where
+    G: VGraph,
+    G::Node: Eq + Hash + Copy,
+    O: FnMut(&mut Vec<G::Node>),
+    C: ClosedSet<G::Node>,
+    F: FnMut(&mut S, G::Node, Option<G::Node>) -> ControlFlow<R>,
+{
+    let mut to_explore = VecDeque::new();
+    to_explore.push_back((start, None));
+    seen.insert(start);
+
+    while let Some((cur, from)) = to_explore.pop_front() {
+        if let ControlFlow::Break(result) = step(state, cur, from) {
+            return Some(result);
         }
 
-        for next in g.out_edges(cur) {
+        let mut neighbors = g.out_edges(cur);
+        order(&mut neighbors);
+        for next in neighbors {
             // Only insert nodes we have not explored yet
-            if !prev.contains_key(&next) {
-                prev.insert(next, cur);
-                to_explore.push_back(next);
+            if seen.insert(next) {
+                to_explore.push_back((next, Some(cur)));
             }
         }
     }
@@ -104,162 +443,1815 @@ where
     F: Fn(G::Node) -> bool,
     H: Fn(G::Node) -> G::Dist,
 {
-    let mut to_explore = PriorityQueue::new();
-    to_explore.push_increase(start, Reverse(heuristic(start)));
-    // Stores the node that this came from on the path, and the best found true distance from the start.
-    let mut prev: HashMap<G::Node, G::Node> = HashMap::new();
-    let mut dist_from_start: HashMap<G::Node, G::Dist> = HashMap::new();
-    dist_from_start.insert(start, G::Dist::zero());
-    while let Some((cur, _priority)) = to_explore.pop() {
-        if is_end(cur) {
-            return Some(back_track(&prev, cur));
-        }
-
-        for next in g.out_edges(cur) {
-            let cur_distance = dist_from_start
-                .get(&cur)
-                .expect("Every node in the explore set should already have a previous distance.");
-            let start_to_next: G::Dist = *cur_distance + g.dist(cur, next);
-            let h_dist = start_to_next + heuristic(next);
-
-            if let Some(best_start_to_next) = dist_from_start.get(&next) {
-                // we already have a path to next that is better than this one, skip this path.
-                if start_to_next >= *best_start_to_next {
-                    continue;
-                }
+    a_star_stream(g, start, is_end, heuristic).map(|path| back_track(&path.prev, path.end))
+}
+
+/// A* search that returns the data of each edge along the found path instead of its nodes.
+///
+/// For puzzles this is the sequence of moves that solves it. When several edges join the same
+/// pair of nodes, the first one listed by `out_edges_with_data` is used.
+pub fn a_star_search_edges<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+) -> Option<Vec<G::EdgeData>>
+where
+    G: LabeledVGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    let path = a_star_search(&g, start, is_end, heuristic)?;
+    let edges = path
+        .windows(2)
+        .map(|window| {
+            let (from, to) = (window[0], window[1]);
+            g.out_edges_with_data(from)
+                .into_iter()
+                .find(|&(next, _)| next == to)
+                .map(|(_, data)| data)
+                .expect("Every step of a found path should be an edge of the graph.")
+        })
+        .collect();
+    Some(edges)
+}
+
+/// Replays `actions` from `start`, returning the nodes visited along the way.
+///
+/// The inverse of `a_star_search_edges`. Each action must be the data of an edge leaving the
+/// node reached so far, otherwise the sequence is not legal and this returns `None`. When
+/// several edges from a node carry the same data, the first one listed is followed.
+pub fn apply_actions<G>(g: G, start: G::Node, actions: &[G::EdgeData]) -> Option<Vec<G::Node>>
+where
+    G: LabeledVGraph,
+    G::Node: Copy,
+    G::EdgeData: PartialEq,
+{
+    let mut path = vec![start];
+    let mut cur = start;
+    for action in actions {
+        (cur, _) = g
+            .out_edges_with_data(cur)
+            .into_iter()
+            .find(|(_, data)| data == action)?;
+        path.push(cur);
+    }
+    Some(path)
+}
+
+/// A node of a path found by `a_star_labeled`, with the two parts of its A* estimate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DistanceLabel<N, D> {
+    pub node: N,
+    /// Distance from the start along the path.
+    pub g: D,
+    /// The heuristic's estimate of the distance left to the end.
+    pub h: D,
+}
+
+/// A* search that labels each node of the found path with its distance from the start and its
+/// heuristic estimate.
+///
+/// Comparing `h` against the true distance left, the last `g` minus this `g`, shows where the
+/// heuristic is tight and where it underestimates.
+pub fn a_star_labeled<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+) -> Option<Vec<DistanceLabel<G::Node, G::Dist>>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    let path = a_star_search(&g, start, is_end, &heuristic)?;
+    let mut from_start = G::Dist::zero();
+    let mut prev = None;
+    let labels = path
+        .into_iter()
+        .map(|node| {
+            if let Some(prev) = prev {
+                from_start = from_start + g.dist(prev, node);
             }
-            to_explore.push_increase(next, Reverse(h_dist));
-
-            if dist_from_start
-                .get(&next)
-                .map(|current_best_start_to_next| start_to_next < *current_best_start_to_next)
-                .unwrap_or(true)
-            {
-                prev.insert(next, cur);
-                dist_from_start.insert(next, start_to_next);
+            prev = Some(node);
+            DistanceLabel {
+                node,
+                g: from_start,
+                h: heuristic(node),
             }
-        }
+        })
+        .collect();
+    Some(labels)
+}
+
+/// A* search that treats every node in `forbidden` as if it was not in the graph.
+///
+/// Forbidden nodes are never explored and never returned as the goal. If `start` is forbidden
+/// there is no path.
+pub fn a_star_avoiding<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+    forbidden: &HashSet<G::Node>,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    if forbidden.contains(&start) {
+        return None;
     }
+    a_star_search(Avoiding { g, forbidden }, start, is_end, heuristic)
+}
 
-    // Ran out of places to explore, end not found.
-    None
+/// Hides a set of nodes from a graph, see `a_star_avoiding`.
+struct Avoiding<'a, G>
+where
+    G: VGraph,
+{
+    g: G,
+    forbidden: &'a HashSet<G::Node>,
 }
 
-pub fn path_length<G>(g: G, path: Vec<G::Node>) -> G::Dist
+impl<G> VGraph for Avoiding<'_, G>
 where
     G: VGraph,
-    G::Node: Copy,
-    G::Dist: Copy + AddAssign + Zero,
+    G::Node: Hash + Eq,
 {
-    let mut distance = G::Dist::zero();
-    for window in path.windows(2) {
-        if let &[from, to] = window {
-            distance += g.dist(from, to);
-        } else {
-            panic!("Windows were not full.");
-        }
+    type Node = G::Node;
+
+    type Dist = G::Dist;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        let mut neighbors = self.g.out_edges(node);
+        neighbors.retain(|next| !self.forbidden.contains(next));
+        neighbors
     }
 
-    distance
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        self.g.dist(from, to)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.g.size_hint()
+    }
 }
 
-/// Given a prev map, where each index points to the previous value.
-/// Returns them in some order.
-fn back_track<A: Copy + Eq + Hash>(prev: &HashMap<A, A>, end: A) -> Vec<A> {
-    let mut path = Vec::new();
-    let mut cur = end;
-    while let Some(next) = prev.get(&cur) {
-        path.push(cur);
-        cur = *next;
+/// A* search restricted to the nodes where `in_bounds` is true, such as a window of a map.
+///
+/// The inclusion counterpart of `a_star_avoiding`, checked lazily as neighbors are found, so
+/// the region never has to be listed. Nodes out of bounds are never explored and never returned
+/// as the goal. If `start` is out of bounds there is no path.
+pub fn a_star_within<G, F, H, B>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+    in_bounds: B,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+    B: Fn(G::Node) -> bool,
+{
+    if !in_bounds(start) {
+        return None;
     }
-    path.push(cur);
-    path.reverse();
+    a_star_search(Within { g, in_bounds }, start, is_end, heuristic)
+}
 
-    path
+/// Hides every node out of bounds, see `a_star_within`.
+struct Within<G, B> {
+    g: G,
+    in_bounds: B,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl<G, B> VGraph for Within<G, B>
+where
+    G: VGraph,
+    G::Node: Copy,
+    B: Fn(G::Node) -> bool,
+{
+    type Node = G::Node;
 
-    struct Ex {}
-    impl Ex {
-        fn new() -> Self {
-            Ex {}
-        }
+    type Dist = G::Dist;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        let mut neighbors = self.g.out_edges(node);
+        neighbors.retain(|&next| (self.in_bounds)(next));
+        neighbors
     }
 
-    impl VGraph for Ex {
-        type Node = usize;
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        self.g.dist(from, to)
+    }
 
-        fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
-            match node {
-                1 => vec![2],
-                2 => vec![3],
-                3 => vec![],
-                _ => vec![],
-            }
-        }
+    fn size_hint(&self) -> Option<usize> {
+        self.g.size_hint()
+    }
+}
 
-        type Dist = i32;
+/// A* search that never adds a dead end to the frontier, unless it is a goal.
+///
+/// Each neighbor is checked with `is_terminal` as it is found, so graphs where most nodes are
+/// dead ends, such as game trees, skip building an empty neighbor list for every one of them.
+/// Only worth it when the graph overrides `is_terminal` with something cheaper than
+/// `out_edges`, otherwise every node's neighbors are built twice.
+pub fn a_star_skip_terminals<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    a_star_search(
+        SkipTerminals { g, is_end: &is_end },
+        start,
+        &is_end,
+        heuristic,
+    )
+}
 
-        // If a node is in the graph, then the distance is 1
-        fn dist(&self, _: Self::Node, _: Self::Node) -> Self::Dist {
-            1
-        }
-    }
+/// Hides dead ends that are not goals, see `a_star_skip_terminals`.
+struct SkipTerminals<'a, G, F> {
+    g: G,
+    is_end: &'a F,
+}
 
-    struct Cycles {}
+impl<G, F> VGraph for SkipTerminals<'_, G, F>
+where
+    G: VGraph,
+    G::Node: Copy,
+    F: Fn(G::Node) -> bool,
+{
+    type Node = G::Node;
 
-    impl VGraph for Cycles {
-        type Node = usize;
+    type Dist = G::Dist;
 
-        type Dist = usize;
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        let mut neighbors = self.g.out_edges(node);
+        neighbors.retain(|&next| (self.is_end)(next) || !self.g.is_terminal(next));
+        neighbors
+    }
 
-        fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
-            match node {
-                1 => vec![2, 3],
-                2 => vec![3, 6],
-                3 => vec![4, 5],
-                4 => vec![10, 5],
-                5 => vec![1],
-                6 => vec![2],
-                7 => vec![8],
-                8 => vec![9],
-                9 => vec![10],
-                10 => vec![1],
-                _ => vec![],
-            }
-        }
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        self.g.dist(from, to)
+    }
 
-        fn dist(&self, from: Self::Node, _to: Self::Node) -> Self::Dist {
-            match from {
-                3 => 3,
-                _ => 1,
-            }
-        }
+    fn size_hint(&self) -> Option<usize> {
+        self.g.size_hint()
     }
+}
 
-    #[test]
-    fn breadth_first_search_works() {
-        assert_eq!(Some(vec![1, 2, 3]), breadth_first_search(Ex::new(), 1, 3));
+/// A* search for the cheapest path to a goal that uses at most `max_hops` edges, even if a
+/// cheaper path with more edges exists.
+///
+/// The best way to reach a node now depends on how many hops are left, so every node is
+/// searched once per hop count it can be reached with. This can be up to `max_hops + 1` times
+/// as much work as `a_star_search`.
+pub fn a_star_max_hops<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+    max_hops: usize,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    let limited = HopLimited { g, max_hops };
+    let path = a_star_search(
+        limited,
+        (start, 0),
+        |(n, _)| is_end(n),
+        |(n, _)| heuristic(n),
+    )?;
+    Some(path.into_iter().map(|(n, _)| n).collect())
+}
+
+/// Pairs each node with the number of edges taken to reach it, see `a_star_max_hops`.
+struct HopLimited<G> {
+    g: G,
+    max_hops: usize,
+}
+
+impl<G> VGraph for HopLimited<G>
+where
+    G: VGraph,
+{
+    type Node = (G::Node, usize);
+
+    type Dist = G::Dist;
+
+    fn out_edges(&self, (node, hops): Self::Node) -> Vec<Self::Node> {
+        if hops >= self.max_hops {
+            return Vec::new();
+        }
+        self.g
+            .out_edges(node)
+            .into_iter()
+            .map(|next| (next, hops + 1))
+            .collect()
     }
 
-    #[test]
-    fn breadth_first_search_for_non_path_terminates() {
-        assert_eq!(None, breadth_first_search(Cycles {}, 1, 33));
+    fn dist(&self, (from, _): Self::Node, (to, _): Self::Node) -> Self::Dist {
+        self.g.dist(from, to)
     }
 
-    #[test]
-    fn path_length_works() {
-        assert_eq!(2, path_length::<Ex>(Ex::new(), vec![1, 2, 3]))
+    fn is_terminal(&self, (node, hops): Self::Node) -> bool {
+        hops >= self.max_hops || self.g.is_terminal(node)
     }
+}
 
-    #[test]
+/// A* search that returns the path with the fewest edges among those tied for cheapest.
+///
+/// Useful when every step has a fixed overhead the distances leave out. The number of edges is
+/// compared only between paths of equal cost, so the result always costs the same as the path
+/// `a_star_search` finds.
+pub fn a_star_fewest_hops<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    a_star_search(FewestHops { g }, start, is_end, |n| HopCounted {
+        dist: heuristic(n),
+        hops: 0,
+    })
+}
+
+/// Counts the edges of every path alongside its distance, see `a_star_fewest_hops`.
+struct FewestHops<G> {
+    g: G,
+}
+
+/// A distance that ties are broken on by the number of edges, compared in field order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct HopCounted<D> {
+    dist: D,
+    hops: usize,
+}
+
+impl<D: Add<Output = D>> Add for HopCounted<D> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        HopCounted {
+            dist: self.dist + other.dist,
+            hops: self.hops + other.hops,
+        }
+    }
+}
+
+impl<D: Zero> Zero for HopCounted<D> {
+    fn zero() -> Self {
+        HopCounted {
+            dist: D::zero(),
+            hops: 0,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.dist.is_zero() && self.hops == 0
+    }
+}
+
+impl<G> VGraph for FewestHops<G>
+where
+    G: VGraph,
+{
+    type Node = G::Node;
+
+    type Dist = HopCounted<G::Dist>;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.g.out_edges(node)
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        HopCounted {
+            dist: self.g.dist(from, to),
+            hops: 1,
+        }
+    }
+
+    fn is_terminal(&self, node: Self::Node) -> bool {
+        self.g.is_terminal(node)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.g.size_hint()
+    }
+}
+
+/// A* search that gives up once every path left to explore is estimated to cost more than
+/// `max_cost`.
+///
+/// Nodes are explored cheapest estimate first, so with an admissible heuristic no path within
+/// the budget is missed: this returns the same path as `a_star_search` when it costs at most
+/// `max_cost`, and `None` otherwise, without exploring anything beyond the budget.
+pub fn a_star_cost_limited<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+    max_cost: G::Dist,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    // The estimated total cost each node was last added to the frontier with.
+    let estimates = RefCell::new(HashMap::new());
+    let over_budget = Cell::new(false);
+    let found = a_star_core(
+        g,
+        start,
+        |_, n| {
+            if estimates.borrow()[&n] > max_cost {
+                over_budget.set(true);
+                return true;
+            }
+            is_end(n)
+        },
+        heuristic,
+        |from_start, h, n| {
+            estimates.borrow_mut().insert(n, from_start + h);
+            Reverse(from_start + h)
+        },
+    )?;
+    if over_budget.get() {
+        return None;
+    }
+    Some(back_track(&found.prev, found.end))
+}
+
+/// A* search where `combine` decides the order nodes are explored in, from a node's distance
+/// from the start and its heuristic estimate. Nodes with the smallest priority go first.
+///
+/// `|g, h| g + h` is plain A*, scaling `h` up gives weighted A*, and `|_, h| h` gives greedy
+/// best first search. Only plain A* with an admissible heuristic is sure to find the shortest
+/// path. The path returned is the best one found to the goal once it is explored.
+pub fn a_star_custom_priority<G, F, H, C, K>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+    combine: C,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+    C: Fn(G::Dist, G::Dist) -> K,
+    K: Ord,
+{
+    a_star_core(
+        g,
+        start,
+        |_, n| is_end(n),
+        heuristic,
+        |from_start, h, _| Reverse(combine(from_start, h)),
+    )
+    .map(|path| back_track(&path.prev, path.end))
+}
+
+/// A* search that checks the heuristic as it goes.
+///
+/// Returns `SearchError::NegativeHeuristic` as soon as the heuristic estimates a negative
+/// distance for a node, since that is always a bug and can make A* return the wrong path.
+pub fn a_star_checked<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+) -> Result<Option<Vec<G::Node>>, SearchError<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    let negative = Cell::new(None);
+    let checked_heuristic = |n| {
+        let h = heuristic(n);
+        if h < G::Dist::zero() && negative.get().is_none() {
+            negative.set(Some(n));
+        }
+        h
+    };
+    // Stop at the next node taken off the frontier once a negative estimate was seen.
+    let found = a_star_core(
+        g,
+        start,
+        |_, n| negative.get().is_some() || is_end(n),
+        checked_heuristic,
+        |from_start, h, _| Reverse(from_start + h),
+    );
+    match negative.get() {
+        Some(node) => Err(SearchError::NegativeHeuristic(node)),
+        None => Ok(found.map(|path| back_track(&path.prev, path.end))),
+    }
+}
+
+/// A* search where any node the heuristic estimates to be zero away is a goal.
+///
+/// Convenient when the heuristic already measures the distance to the goal, such as how far a
+/// running total is from zero.
+pub fn a_star_heuristic_goal<G, H>(g: G, start: G::Node, heuristic: H) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    H: Fn(G::Node) -> G::Dist,
+{
+    a_star_search(g, start, |n| heuristic(n).is_zero(), &heuristic)
+}
+
+/// The shortest path that visits `waypoints` in order, made by joining the shortest path
+/// between each consecutive pair.
+///
+/// Returns `None` if any waypoint can not be reached from the one before it.
+pub fn path_through_waypoints<G>(g: G, waypoints: &[G::Node]) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let Some((&first, _)) = waypoints.split_first() else {
+        return Some(Vec::new());
+    };
+    let mut path = vec![first];
+    for leg in waypoints.windows(2) {
+        let (from, to) = (leg[0], leg[1]);
+        let segment = a_star_search(&g, from, |n| n == to, |_| G::Dist::zero())?;
+        // The segment starts where the previous one ended.
+        path.extend(segment.into_iter().skip(1));
+    }
+    Some(path)
+}
+
+/// The cheapest cycle that starts and ends at `node`, as a path beginning and ending with it.
+///
+/// Searches from every successor of `node` back to it, so it costs one search per outgoing
+/// edge. Returns `None` if `node` is not on any cycle.
+pub fn shortest_cycle_through<G>(g: G, node: G::Node) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let mut best: Option<(G::Dist, Vec<G::Node>)> = None;
+    for next in g.out_edges(node) {
+        let Some(back) = a_star_search(&g, next, |n| n == node, |_| G::Dist::zero()) else {
+            continue;
+        };
+        let mut cycle = vec![node];
+        cycle.extend(back);
+        let cost = cycle
+            .windows(2)
+            .fold(G::Dist::zero(), |total, w| total + g.dist(w[0], w[1]));
+        if best.as_ref().is_none_or(|(best_cost, _)| cost < *best_cost) {
+            best = Some((cost, cycle));
+        }
+    }
+    best.map(|(_, cycle)| cycle)
+}
+
+/// Like `a_star_search`, but ties between nodes with the same estimated cost go to the smallest
+/// node.
+///
+/// `a_star_search` breaks ties by the order nodes were added to the frontier, so the path it
+/// picks among equally short ones depends on the order of `out_edges`. This always gives the
+/// same path for the same graph, however its neighbors are ordered.
+pub fn a_star_deterministic<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    a_star_core(
+        g,
+        start,
+        |_, n| is_end(n),
+        heuristic,
+        |from_start, h, n| Reverse((from_start + h, n)),
+    )
+    .map(|path| back_track(&path.prev, path.end))
+}
+
+/// A* search from `start` to `end` that first checks `end` can be reached at all.
+///
+/// The check is a breadth first search backward from `end` over `in_edges`, stopping as soon as
+/// it finds `start`. When the goal sits in a part of the graph `start` can not reach, this
+/// returns `None` after exploring only the nodes that lead to the goal, instead of everything
+/// reachable from `start`. When the goal is reachable the check is extra work, so it pays off
+/// when the area around the goal is small.
+pub fn a_star_with_precheck<G, H>(
+    g: G,
+    start: G::Node,
+    end: G::Node,
+    heuristic: H,
+) -> Option<Vec<G::Node>>
+where
+    G: ReversibleVGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    H: Fn(G::Node) -> G::Dist,
+{
+    let unreachable = explore(Reversed { g: &g }, end, &mut (), |_, cur, _| {
+        if cur == start {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .is_none();
+    if unreachable {
+        return None;
+    }
+
+    a_star_search(g, start, |n| n == end, heuristic)
+}
+
+/// How `a_star_mode` weighs the heuristic against the distance already travelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode<D> {
+    /// Plain A*, the heuristic is used as given.
+    Admissible,
+    /// Weighted A*, the heuristic is multiplied by the weight. Weights above one head for the
+    /// goal more greedily, finding a path sooner but possibly not the shortest one.
+    Weighted(D),
+}
+
+/// A path found by `a_star_mode`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundPath<N> {
+    pub path: Vec<N>,
+    /// True when the search mode guarantees no shorter path exists, assuming the heuristic is
+    /// admissible.
+    pub optimal: bool,
+}
+
+/// A* search in the given `mode`, also reporting whether the path is guaranteed to be
+/// optimal.
+///
+/// With an admissible heuristic, `Admissible` and weights of at most one always give the
+/// shortest path. A larger weight gives a path at most `weight` times as long as the shortest,
+/// which is reported as not optimal even when it happens to be.
+pub fn a_star_mode<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+    mode: SearchMode<G::Dist>,
+) -> Option<FoundPath<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + One + Mul<Output = G::Dist> + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    let weight = match mode {
+        SearchMode::Admissible => G::Dist::one(),
+        SearchMode::Weighted(weight) => weight,
+    };
+    let path = a_star_search(g, start, is_end, |n| heuristic(n) * weight)?;
+    Some(FoundPath {
+        path,
+        optimal: weight <= G::Dist::one(),
+    })
+}
+
+/// Like `a_star_search`, but the path is handed out as an iterator over its nodes from start
+/// to end.
+///
+/// The path is back tracked when the first node is requested, all at once into a `Vec` held
+/// by the iterator, so a caller that drops the stream early never pays for it. Going forward
+/// from the start needs the whole path first, see `PredecessorPath` to walk it from the end
+/// without allocating.
+pub fn a_star_stream<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+) -> Option<PathStream<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    a_star_core(
+        g,
+        start,
+        |_, n| is_end(n),
+        heuristic,
+        |from_start, h, _| Reverse(from_start + h),
+    )
+}
+
+/// A* search where `is_end` is given the best path found to each candidate node.
+///
+/// Useful when reaching a goal depends on how a node was reached, not only the node itself.
+/// The path is rebuilt for every node taken off the frontier, which costs time proportional to
+/// its length, so prefer `a_star_search` when the node alone decides.
+pub fn a_star_search_path_goal<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(&[G::Node]) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    a_star_core(
+        g,
+        start,
+        |prev, n| is_end(&back_track(prev, n)),
+        heuristic,
+        |from_start, h, _| Reverse(from_start + h),
+    )
+    .map(|path| back_track(&path.prev, path.end))
+}
+
+/// Most nodes a search reserves room for up front, however many `size_hint` promises.
+const MAX_RESERVED: usize = 1 << 16;
+
+/// Room to reserve in a search's maps, from the graph's `size_hint` but capped so a huge graph
+/// does not cost memory a short search never uses.
+fn reserved_capacity<G: VGraph>(g: &G) -> usize {
+    g.size_hint().unwrap_or(0).min(MAX_RESERVED)
+}
+
+/// Shared A* loop, `is_end` is also given the map of previous nodes found so far.
+///
+/// `priority` turns a node's distance from the start and its heuristic estimate into its place
+/// in the frontier, the greatest priority is explored first.
+fn a_star_core<G, F, H, P, K>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+    priority: P,
+) -> Option<PathStream<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: FnMut(&HashMap<G::Node, G::Node>, G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+    P: Fn(G::Dist, G::Dist, G::Node) -> K,
+    K: Ord,
+{
+    let capacity = reserved_capacity(&g);
+    let mut to_explore = PriorityQueue::new();
+    to_explore.push_increase(start, priority(G::Dist::zero(), heuristic(start), start));
+    // Stores the node that this came from on the path, and the best found true distance from the start.
+    let mut prev: HashMap<G::Node, G::Node> = HashMap::with_capacity(capacity);
+    let mut dist_from_start: HashMap<G::Node, G::Dist> = HashMap::with_capacity(capacity);
+    dist_from_start.insert(start, G::Dist::zero());
+    let search = AStarBuffers {
+        prev: &mut prev,
+        dist_from_start: &mut dist_from_start,
+        to_explore: &mut to_explore,
+    };
+    match search.run(&g, is_end, heuristic, priority, usize::MAX) {
+        Explored::Goal(end) => Some(PathStream {
+            prev,
+            end,
+            remaining: None,
+        }),
+        // Ran out of places to explore, end not found.
+        Explored::Exhausted | Explored::Paused => None,
+    }
+}
+
+/// The state of an A* search, borrowed so a search can be paused and picked up again, see
+/// `SearchContext::resume`.
+pub(crate) struct AStarBuffers<'a, N, D, K>
+where
+    N: Hash + Eq,
+    K: Ord,
+{
+    pub(crate) prev: &'a mut HashMap<N, N>,
+    pub(crate) dist_from_start: &'a mut HashMap<N, D>,
+    pub(crate) to_explore: &'a mut PriorityQueue<N, K>,
+}
+
+/// Where `AStarBuffers::run` stopped.
+pub(crate) enum Explored<N> {
+    Goal(N),
+    Exhausted,
+    Paused,
+}
+
+impl<N, D, K> AStarBuffers<'_, N, D, K>
+where
+    N: Hash + Eq + Copy,
+    D: Zero + Ord + Copy,
+    K: Ord,
+{
+    /// Explores at most `max_steps` nodes from the frontier, stopping early at a goal.
+    pub(crate) fn run<G, F, H, P>(
+        self,
+        g: &G,
+        mut is_end: F,
+        heuristic: H,
+        priority: P,
+        max_steps: usize,
+    ) -> Explored<N>
+    where
+        G: VGraph<Node = N, Dist = D>,
+        F: FnMut(&HashMap<N, N>, N) -> bool,
+        H: Fn(N) -> D,
+        P: Fn(D, D, N) -> K,
+    {
+        let AStarBuffers {
+            prev,
+            dist_from_start,
+            to_explore,
+        } = self;
+        for _ in 0..max_steps {
+            let Some((cur, _priority)) = to_explore.pop() else {
+                return Explored::Exhausted;
+            };
+            if is_end(prev, cur) {
+                return Explored::Goal(cur);
+            }
+
+            for next in g.out_edges(cur) {
+                let cur_distance = dist_from_start.get(&cur).expect(
+                    "Every node in the explore set should already have a previous distance.",
+                );
+                let start_to_next: D = *cur_distance + g.dist(cur, next);
+                let h = heuristic(next);
+
+                if let Some(best_start_to_next) = dist_from_start.get(&next) {
+                    // we already have a path to next that is better than this one, skip this path.
+                    if start_to_next >= *best_start_to_next {
+                        continue;
+                    }
+                }
+                to_explore.push_increase(next, priority(start_to_next, h, next));
+
+                if dist_from_start
+                    .get(&next)
+                    .map(|current_best_start_to_next| start_to_next < *current_best_start_to_next)
+                    .unwrap_or(true)
+                {
+                    prev.insert(next, cur);
+                    dist_from_start.insert(next, start_to_next);
+                }
+            }
+        }
+
+        Explored::Paused
+    }
+}
+
+/// Iterator over a found path, see `a_star_stream`.
+pub struct PathStream<N> {
+    prev: HashMap<N, N>,
+    end: N,
+    // Nodes left to yield, last element first. Filled on the first call to `next`.
+    remaining: Option<Vec<N>>,
+}
+
+impl<N> Iterator for PathStream<N>
+where
+    N: Copy + Eq + Hash,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (prev, end) = (&self.prev, self.end);
+        self.remaining
+            .get_or_insert_with(|| PredecessorPath::new(prev, end).collect())
+            .pop()
+    }
+}
+
+/// Dijkstra's algorithm that gives up once `deadline` passes.
+///
+/// Returns the distance from `start` to every node settled before the deadline. Settled
+/// distances are exact, nodes that were not reached in time are absent.
+pub fn dijkstra_timed<G>(g: G, start: G::Node, deadline: Instant) -> HashMap<G::Node, G::Dist>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    dijkstra_until(g, start, || Instant::now() >= deadline).dist
+}
+
+/// A path found by `a_star_anytime`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnytimePath<N> {
+    /// The search reached the goal before the deadline.
+    Complete(Vec<N>),
+    /// The deadline passed first. The path leads to the explored node the heuristic judged
+    /// closest to the goal.
+    Partial(Vec<N>),
+}
+
+/// A* search that gives up once `deadline` passes, for callers that must act on something in
+/// time.
+///
+/// If the goal is reached first this is the same path as `a_star_search`. Otherwise it is the
+/// path to the explored node with the lowest heuristic estimate, earliest explored on ties. A
+/// partial path is only a best guess at where to head: it is not the start of a shortest path
+/// to the goal, and the goal may not even be reachable from its end. Returns `None` if every
+/// reachable node was explored without finding the goal.
+pub fn a_star_anytime<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+    deadline: Instant,
+) -> Option<AnytimePath<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    a_star_until(g, start, is_end, heuristic, || Instant::now() >= deadline)
+}
+
+/// `a_star_anytime` that gives up once `out_of_time` returns true, checked after each node is
+/// explored.
+fn a_star_until<G, F, H, T>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+    mut out_of_time: T,
+) -> Option<AnytimePath<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+    T: FnMut() -> bool,
+{
+    let best: Cell<Option<(G::Node, G::Dist)>> = Cell::new(None);
+    let timed_out = Cell::new(false);
+    let found = a_star_core(
+        g,
+        start,
+        |_, n| {
+            if is_end(n) {
+                return true;
+            }
+            let h = heuristic(n);
+            if best.get().is_none_or(|(_, best_h)| h < best_h) {
+                best.set(Some((n, h)));
+            }
+            if out_of_time() {
+                timed_out.set(true);
+                return true;
+            }
+            false
+        },
+        &heuristic,
+        |from_start, h, _| Reverse(from_start + h),
+    )?;
+    if timed_out.get() {
+        let (closest, _) = best.get().expect("The node explored last was recorded.");
+        return Some(AnytimePath::Partial(back_track(&found.prev, closest)));
+    }
+    Some(AnytimePath::Complete(back_track(&found.prev, found.end)))
+}
+
+/// Distances and previous nodes found by Dijkstra's algorithm.
+pub(crate) struct ShortestPaths<N, D> {
+    pub(crate) dist: HashMap<N, D>,
+    pub(crate) prev: HashMap<N, N>,
+}
+
+/// Settles nodes in order of distance from `start` until the graph is exhausted or `stop`
+/// returns true.
+pub(crate) fn dijkstra_until<G, S>(
+    g: G,
+    start: G::Node,
+    mut stop: S,
+) -> ShortestPaths<G::Node, G::Dist>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+    S: FnMut() -> bool,
+{
+    let mut settle = dijkstra_iter(g, start);
+    while !stop() && settle.next().is_some() {}
+
+    ShortestPaths {
+        dist: settle.settled,
+        prev: settle.prev,
+    }
+}
+
+/// Iterates over the nodes reachable from `start` in order of increasing distance, along with
+/// that distance.
+///
+/// Each distance is final when it is yielded, so callers can stop as soon as they have seen
+/// what they need.
+pub fn dijkstra_iter<G>(g: G, start: G::Node) -> DijkstraIter<G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let capacity = reserved_capacity(&g);
+    let mut to_explore = PriorityQueue::new();
+    to_explore.push(start, Reverse(G::Dist::zero()));
+    DijkstraIter {
+        g,
+        to_explore,
+        settled: HashMap::with_capacity(capacity),
+        prev: HashMap::with_capacity(capacity),
+    }
+}
+
+/// Iterator returned by `dijkstra_iter`.
+pub struct DijkstraIter<G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq,
+    G::Dist: Ord,
+{
+    g: G,
+    to_explore: PriorityQueue<G::Node, Reverse<G::Dist>>,
+    settled: HashMap<G::Node, G::Dist>,
+    // Best known previous node, for both settled nodes and nodes still being explored.
+    prev: HashMap<G::Node, G::Node>,
+}
+
+impl<G> Iterator for DijkstraIter<G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    type Item = (G::Node, G::Dist);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (cur, Reverse(cur_distance)) = self.to_explore.pop()?;
+        self.settled.insert(cur, cur_distance);
+
+        for next in self.g.out_edges(cur) {
+            if self.settled.contains_key(&next) {
+                continue;
+            }
+            let start_to_next = cur_distance + self.g.dist(cur, next);
+            let improves = self
+                .to_explore
+                .get_priority(&next)
+                .map(|Reverse(best)| start_to_next < *best)
+                .unwrap_or(true);
+            if improves {
+                self.to_explore.push(next, Reverse(start_to_next));
+                self.prev.insert(next, cur);
+            }
+        }
+
+        Some((cur, cur_distance))
+    }
+}
+
+/// `dijkstra_iter` that also yields the node each settled node was reached from, `None` for
+/// `start`.
+///
+/// The predecessors form the shortest path tree, so it can be built or drawn as the search
+/// spreads out. Every predecessor is settled before the nodes it leads to.
+pub fn dijkstra_tree_iter<G>(g: G, start: G::Node) -> DijkstraTreeIter<G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    DijkstraTreeIter {
+        settle: dijkstra_iter(g, start),
+    }
+}
+
+/// Iterator returned by `dijkstra_tree_iter`.
+pub struct DijkstraTreeIter<G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq,
+    G::Dist: Ord,
+{
+    settle: DijkstraIter<G>,
+}
+
+impl<G> Iterator for DijkstraTreeIter<G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    type Item = (G::Node, G::Dist, Option<G::Node>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (cur, cur_distance) = self.settle.next()?;
+        // The best previous node can no longer change once a node is settled.
+        Some((cur, cur_distance, self.settle.prev.get(&cur).copied()))
+    }
+}
+
+/// The shortest distance from every node that can reach `goal` to it, found with a Dijkstra
+/// search over the reversed edges.
+///
+/// These are the perfect heuristic for a search towards `goal`. Scaling them down, or taking
+/// the smaller of them and another estimate, gives admissible and consistent heuristics of any
+/// quality. Nodes missing from the map can not reach `goal`.
+pub fn distances_to<G>(g: G, goal: G::Node) -> HashMap<G::Node, G::Dist>
+where
+    G: ReversibleVGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    dijkstra_iter(Reversed { g }, goal).collect()
+}
+
+/// A graph with every edge turned around.
+pub(crate) struct Reversed<G> {
+    pub(crate) g: G,
+}
+
+impl<G> VGraph for Reversed<G>
+where
+    G: ReversibleVGraph,
+{
+    type Node = G::Node;
+
+    type Dist = G::Dist;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.g.in_edges(node)
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        self.g.dist(to, from)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.g.size_hint()
+    }
+}
+
+/// Finds the closest node to `start` matching `pred`, along with its distance.
+///
+/// `start` itself is a candidate. Unlike a goal directed search, nothing needs to be known
+/// about where the matches are.
+pub fn nearest<G, F>(g: G, start: G::Node, pred: F) -> Option<(G::Node, G::Dist)>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+{
+    dijkstra_iter(g, start).find(|&(node, _)| pred(node))
+}
+
+/// Every node matching `is_end`, in order of increasing distance from `start`, along with its
+/// shortest path and distance.
+///
+/// A stream of `nearest` results that only searches as far as the goals taken from it. Ends
+/// once every node reachable from `start` has been settled.
+pub fn goals_by_distance<G, F>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+) -> impl Iterator<Item = (G::Node, Vec<G::Node>, G::Dist)>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+{
+    let mut settle = dijkstra_iter(g, start);
+    std::iter::from_fn(move || loop {
+        let (node, dist) = settle.next()?;
+        if is_end(node) {
+            return Some((node, back_track(&settle.prev, node), dist));
+        }
+    })
+}
+
+/// The cost of the shortest path from `from` to `to`, without building the path.
+pub fn distance<G>(g: G, from: G::Node, to: G::Node) -> Option<G::Dist>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    nearest(g, from, |node| node == to).map(|(_, dist)| dist)
+}
+
+/// The distance from `start` to each of `nodes`, `None` for those that can not be reached.
+///
+/// Every queried node gets an entry, like a row of a distance matrix. Dijkstra's algorithm
+/// stops as soon as all of them are settled, but has to exhaust the reachable graph before
+/// it knows a node is unreachable.
+pub fn shortest_distances_dense<G>(
+    g: G,
+    start: G::Node,
+    nodes: &[G::Node],
+) -> HashMap<G::Node, Option<G::Dist>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let mut distances: HashMap<G::Node, Option<G::Dist>> =
+        nodes.iter().map(|&node| (node, None)).collect();
+    let mut remaining: HashSet<G::Node> = nodes.iter().copied().collect();
+    let mut settle = dijkstra_iter(g, start);
+    while !remaining.is_empty() {
+        let Some((node, dist)) = settle.next() else {
+            break;
+        };
+        if remaining.remove(&node) {
+            distances.insert(node, Some(dist));
+        }
+    }
+    distances
+}
+
+pub fn path_length<G>(g: G, path: Vec<G::Node>) -> G::Dist
+where
+    G: VGraph,
+    G::Node: Copy,
+    G::Dist: Copy + AddAssign + Zero,
+{
+    let mut distance = G::Dist::zero();
+    for window in path.windows(2) {
+        if let &[from, to] = window {
+            distance += g.dist(from, to);
+        } else {
+            panic!("Windows were not full.");
+        }
+    }
+
+    distance
+}
+
+/// True if every consecutive pair of `path` is joined by an edge in `out_edges`.
+///
+/// `path_length` assumes the path is valid, so check paths from outside the program with this
+/// first. Paths of zero or one node have no edges to check and are always valid.
+pub fn is_valid_path<G>(g: G, path: &[G::Node]) -> bool
+where
+    G: VGraph,
+    G::Node: Eq + Copy,
+{
+    path.windows(2)
+        .all(|step| g.out_edges(step[0]).contains(&step[1]))
+}
+
+/// Drops the first node of `path`, leaving only the nodes moved to. A path of just the start
+/// becomes empty.
+pub fn path_without_start<N>(mut path: Vec<N>) -> Vec<N> {
+    if !path.is_empty() {
+        path.remove(0);
+    }
+    path
+}
+
+/// True if some node appears more than once in `path`, meaning the path loops.
+pub fn has_repeated_node<N>(path: &[N]) -> bool
+where
+    N: Hash + Eq + Copy,
+{
+    first_repeat(path).is_some()
+}
+
+/// The first node in `path` that was already visited earlier in the path.
+pub fn first_repeat<N>(path: &[N]) -> Option<N>
+where
+    N: Hash + Eq + Copy,
+{
+    let mut seen = HashSet::new();
+    path.iter().copied().find(|&node| !seen.insert(node))
+}
+
+/// Given a prev map, where each index points to the previous value.
+/// Returns them in some order.
+pub(crate) fn back_track<A: Copy + Eq + Hash>(prev: &HashMap<A, A>, end: A) -> Vec<A> {
+    let mut path: Vec<A> = PredecessorPath::new(prev, end).collect();
+    path.reverse();
+
+    path
+}
+
+/// Iterator over a path stored as a map from each node to the one before it, from `end` back
+/// to the start, the first node with no entry in the map.
+///
+/// Nodes are looked up one at a time, so walking a long path needs no extra memory. The map must
+/// not loop back on itself, or the iterator never ends.
+pub struct PredecessorPath<'a, N> {
+    prev: &'a HashMap<N, N>,
+    next: Option<N>,
+}
+
+impl<'a, N> PredecessorPath<'a, N> {
+    pub fn new(prev: &'a HashMap<N, N>, end: N) -> Self {
+        PredecessorPath {
+            prev,
+            next: Some(end),
+        }
+    }
+}
+
+impl<N> Iterator for PredecessorPath<'_, N>
+where
+    N: Copy + Eq + Hash,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cur = self.next?;
+        self.next = self.prev.get(&cur).copied();
+        Some(cur)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::{Cycles, Ex, RingPuzzle, RingState};
+    use std::time::Duration;
+
+    #[test]
+    fn breadth_first_search_works() {
+        assert_eq!(Some(vec![1, 2, 3]), breadth_first_search(Ex::new(), 1, 3));
+    }
+
+    #[test]
+    fn breadth_first_search_for_non_path_terminates() {
+        assert_eq!(None, breadth_first_search(Cycles {}, 1, 33));
+    }
+
+    /// Diamond shaped graph that hands out its neighbors in `HashSet` order.
+    struct Unordered {}
+
+    impl VGraph for Unordered {
+        type Node = usize;
+
+        type Dist = usize;
+
+        fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+            let neighbors: HashSet<usize> = match node {
+                1 => HashSet::from([2, 3, 4]),
+                2..=4 => HashSet::from([5]),
+                _ => HashSet::new(),
+            };
+            neighbors.into_iter().collect()
+        }
+
+        fn dist(&self, _from: Self::Node, _to: Self::Node) -> Self::Dist {
+            1
+        }
+    }
+
+    #[test]
+    fn breadth_first_search_sorted_is_reproducible() {
+        for _ in 0..10 {
+            assert_eq!(
+                Some(vec![1, 2, 5]),
+                breadth_first_search_sorted(Unordered {}, 1, 5)
+            );
+            assert_eq!(
+                Some(vec![1, 4, 5]),
+                breadth_first_search_sorted_by(Unordered {}, 1, 5, |a, b| b.cmp(a))
+            );
+        }
+    }
+
+    #[test]
+    fn breadth_first_search_does_not_revisit_start() {
+        assert_eq!(
+            Some(vec![2, 3, 5, 1]),
+            breadth_first_search(Cycles {}, 2, 1)
+        );
+    }
+
+    #[test]
+    fn explore_counts_reachable_nodes() {
+        let mut count = 0;
+        let result: Option<()> = explore(Cycles {}, 7, &mut count, |count, _, _| {
+            *count += 1;
+            ControlFlow::Continue(())
+        });
+        assert_eq!(None, result);
+        assert_eq!(10, count);
+    }
+
+    #[test]
+    fn explore_stops_on_break() {
+        let mut visited = Vec::new();
+        let found = explore(Cycles {}, 1, &mut visited, |visited, node, from| {
+            visited.push(node);
+            if node == 4 {
+                ControlFlow::Break(from)
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(Some(Some(3)), found);
+        assert_eq!(vec![1, 2, 3, 6, 4], visited);
+    }
+
+    #[test]
+    fn path_length_works() {
+        assert_eq!(2, path_length::<Ex>(Ex::new(), vec![1, 2, 3]))
+    }
+
+    #[test]
+    fn distance_matches_path_length() {
+        for from in 1..=10 {
+            for to in 1..=10 {
+                let path = a_star_search(Cycles {}, from, |n| n == to, |_| 0);
+                assert_eq!(
+                    path.map(|path| path_length(Cycles {}, path)),
+                    distance(Cycles {}, from, to),
+                    "{from} to {to}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn valid_and_broken_paths() {
+        assert!(is_valid_path(Cycles {}, &[1, 3, 4, 10]));
+        assert!(is_valid_path(Cycles {}, &[7, 8, 9, 10, 1, 2, 6, 2]));
+        assert!(is_valid_path(Cycles {}, &[5]));
+        assert!(is_valid_path(Cycles {}, &[]));
+        // 3 does not lead to 10, and no edge goes into 7.
+        assert!(!is_valid_path(Cycles {}, &[1, 3, 10]));
+        assert!(!is_valid_path(Cycles {}, &[6, 7, 8]));
+        // Edges only go one way.
+        assert!(!is_valid_path(Cycles {}, &[3, 1]));
+    }
+
+    #[test]
+    fn path_without_start_on_ex() {
+        let path = breadth_first_search(Ex::new(), 1, 3).unwrap();
+        assert_eq!(vec![2, 3], path_without_start(path));
+        let path = breadth_first_search(Ex::new(), 3, 3).unwrap();
+        assert_eq!(Vec::<usize>::new(), path_without_start(path));
+    }
+
+    #[test]
+    fn repeated_nodes() {
+        assert!(!has_repeated_node::<usize>(&[]));
+        assert!(!has_repeated_node(&[1, 3, 4, 10]));
+        assert_eq!(None, first_repeat(&[1, 3, 4, 10]));
+        assert!(has_repeated_node(&[1, 2, 6, 2, 3]));
+        assert_eq!(Some(2), first_repeat(&[1, 2, 6, 2, 3, 5, 1]));
+    }
+
+    /// A grid of equally short routes, with every node's neighbors rotated by `shift`.
+    struct Rotated {
+        shift: usize,
+    }
+
+    impl VGraph for Rotated {
+        type Node = (usize, usize);
+
+        type Dist = usize;
+
+        fn out_edges(&self, (x, y): Self::Node) -> Vec<Self::Node> {
+            let mut neighbors: Vec<_> = [(x + 1, y), (x, y + 1)]
+                .into_iter()
+                .filter(|&(x, y)| x <= 3 && y <= 3)
+                .collect();
+            let len = neighbors.len();
+            if len > 0 {
+                neighbors.rotate_left(self.shift % len);
+            }
+            neighbors
+        }
+
+        fn dist(&self, _from: Self::Node, _to: Self::Node) -> Self::Dist {
+            1
+        }
+    }
+
+    #[test]
+    fn a_star_deterministic_ignores_neighbor_order() {
+        let expected = a_star_deterministic(Rotated { shift: 0 }, (0, 0), |n| n == (3, 3), |_| 0);
+        assert_eq!(
+            6,
+            path_length(Rotated { shift: 0 }, expected.clone().unwrap())
+        );
+        for shift in 1..4 {
+            assert_eq!(
+                expected,
+                a_star_deterministic(Rotated { shift }, (0, 0), |n| n == (3, 3), |_| 0)
+            );
+        }
+        for _ in 0..10 {
+            assert_eq!(
+                Some(vec![1, 2, 5]),
+                a_star_deterministic(Unordered {}, 1, |n| n == 5, |_| 0)
+            );
+        }
+    }
+
+    #[test]
+    fn path_through_waypoints_on_cycles() {
+        assert_eq!(
+            Some(vec![1, 2, 6, 2, 3, 4, 10]),
+            path_through_waypoints(Cycles {}, &[1, 6, 4, 10])
+        );
+        assert_eq!(Some(vec![7]), path_through_waypoints(Cycles {}, &[7]));
+        assert_eq!(None, path_through_waypoints(Cycles {}, &[1, 4, 7, 10]));
+    }
+
+    #[test]
+    fn a_star_mode_reports_optimality() {
+        let plain = a_star_mode(Cycles {}, 1, |n| n == 10, |_| 0, SearchMode::Admissible).unwrap();
+        assert!(plain.optimal);
+        assert_eq!(vec![1, 3, 4, 10], plain.path);
+
+        let h = |n: usize| if n == 10 { 0 } else { 1 };
+        let weighted = a_star_mode(Cycles {}, 1, |n| n == 10, h, SearchMode::Weighted(3)).unwrap();
+        assert!(!weighted.optimal);
+        assert_eq!(5, path_length(Cycles {}, weighted.path));
+        let unit = a_star_mode(Cycles {}, 1, |n| n == 10, h, SearchMode::Weighted(1)).unwrap();
+        assert!(unit.optimal);
+    }
+
+    /// Counts the calls to `out_edges` of the wrapped graph.
+    struct CountOutEdges<G> {
+        g: G,
+        calls: Cell<usize>,
+    }
+
+    impl<G: VGraph> VGraph for CountOutEdges<G> {
+        type Node = G::Node;
+
+        type Dist = G::Dist;
+
+        fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+            self.calls.set(self.calls.get() + 1);
+            self.g.out_edges(node)
+        }
+
+        fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+            self.g.dist(from, to)
+        }
+    }
+
+    impl<G: ReversibleVGraph> ReversibleVGraph for CountOutEdges<G> {
+        fn in_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+            self.g.in_edges(node)
+        }
+    }
+
+    #[test]
+    fn a_star_with_precheck_skips_unreachable_goal() {
+        let g = CountOutEdges {
+            g: Cycles {},
+            calls: Cell::new(0),
+        };
+        // Nothing leads into 7.
+        assert_eq!(None, a_star_with_precheck(&g, 1, 7, |_| 0));
+        assert_eq!(0, g.calls.get());
+
+        assert_eq!(
+            Some(vec![1, 3, 4, 10]),
+            a_star_with_precheck(&g, 1, 10, |_| 0)
+        );
+        assert!(g.calls.get() > 0);
+    }
+
+    #[test]
+    fn a_star_max_hops_takes_fewer_edges() {
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(0, 1, 1);
+        g.add_edge(1, 2, 1);
+        g.add_edge(2, 3, 1);
+        g.add_edge(0, 3, 10);
+        assert_eq!(
+            Some(vec![0, 1, 2, 3]),
+            a_star_max_hops(&g, 0, |n| n == 3, |_| 0, 3)
+        );
+        assert_eq!(
+            Some(vec![0, 3]),
+            a_star_max_hops(&g, 0, |n| n == 3, |_| 0, 2)
+        );
+
+        assert_eq!(
+            Some(vec![1, 3, 4, 10]),
+            a_star_max_hops(Cycles {}, 1, |n| n == 10, |_| 0, 3)
+        );
+        assert_eq!(None, a_star_max_hops(Cycles {}, 1, |n| n == 10, |_| 0, 2));
+    }
+
+    #[test]
+    fn a_star_fewest_hops_breaks_ties_on_edges() {
+        // 0 1 2 3 and 0 4 3 both cost 6, the path with more edges reaches 3 first.
+        let graph = |last_edge| {
+            let mut g = AdjacencyListGraph::new();
+            g.add_edge(0, 1, 1);
+            g.add_edge(1, 2, 1);
+            g.add_edge(2, 3, 4);
+            g.add_edge(0, 4, 5);
+            g.add_edge(4, 3, last_edge);
+            g
+        };
+        let g = graph(1);
+        assert_eq!(
+            Some(vec![0, 1, 2, 3]),
+            a_star_search(&g, 0, |n| n == 3, |_| 0)
+        );
+        assert_eq!(
+            Some(vec![0, 4, 3]),
+            a_star_fewest_hops(&g, 0, |n| n == 3, |_| 0)
+        );
+
+        // Fewer edges never wins over a lower cost.
+        assert_eq!(
+            Some(vec![0, 1, 2, 3]),
+            a_star_fewest_hops(graph(2), 0, |n| n == 3, |_| 0)
+        );
+    }
+
+    #[test]
+    fn a_star_custom_priority_plain_and_greedy() {
+        for end in 1..=10 {
+            assert_eq!(
+                a_star_search(Cycles {}, 1, |n| n == end, |_| 0),
+                a_star_custom_priority(Cycles {}, 1, |n| n == end, |_| 0, |g, h| g + h)
+            );
+        }
+
+        // 1 looks closer to the goal, but only 2 is on the shortest path.
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(0, 1, 10);
+        g.add_edge(1, 3, 1);
+        g.add_edge(0, 2, 1);
+        g.add_edge(2, 3, 1);
+        let heuristic = |n| u32::from(n == 2);
+        let is_end = |n| n == 3;
+        assert_eq!(
+            Some(vec![0, 2, 3]),
+            a_star_custom_priority(&g, 0, is_end, heuristic, |g, h| g + h)
+        );
+        assert_eq!(
+            Some(vec![0, 1, 3]),
+            a_star_custom_priority(&g, 0, is_end, heuristic, |_, h| h)
+        );
+        // Ties in `h` go to the nearer node.
+        assert_eq!(
+            Some(vec![0, 2, 3]),
+            a_star_custom_priority(&g, 0, is_end, |_| 0, |g, h| (h, g))
+        );
+    }
+
+    #[test]
+    fn a_star_cost_limited_stops_at_budget() {
+        // 1 to 10 costs 5.
+        assert_eq!(
+            Some(vec![1, 3, 4, 10]),
+            a_star_cost_limited(Cycles {}, 1, |n| n == 10, |_| 0, 5)
+        );
+        assert_eq!(
+            None,
+            a_star_cost_limited(Cycles {}, 1, |n| n == 10, |_| 0, 4)
+        );
+        assert_eq!(
+            None,
+            a_star_cost_limited(Cycles {}, 1, |_| false, |_| 0, 100)
+        );
+
+        // Nothing costing more than the budget is expanded.
+        let g = ExpansionRecorder::new(Cycles {});
+        assert_eq!(None, a_star_cost_limited(&g, 1, |n| n == 10, |_| 0, 1));
+        let expanded: HashSet<usize> = g
+            .log()
+            .into_iter()
+            .filter_map(|call| match call {
+                GraphCall::OutEdges { node, .. } => Some(node),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(HashSet::from([1, 2, 3]), expanded);
+    }
+
+    #[test]
+    fn shortest_cycle_through_on_cycles() {
+        assert_eq!(Some(vec![2, 6, 2]), shortest_cycle_through(Cycles {}, 2));
+        assert_eq!(Some(vec![5, 1, 3, 5]), shortest_cycle_through(Cycles {}, 5));
+        assert_eq!(None, shortest_cycle_through(Cycles {}, 7));
+    }
+
+    /// Node 2 is first reached by the expensive edge 0 -> 2, and only later through 1. Unless
+    /// its frontier priority drops when the cheaper path is found, the direct edge 0 -> 3 wins.
+    fn worse_then_better() -> AdjacencyListGraph<usize, u32> {
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(0, 2, 10);
+        g.add_edge(0, 1, 1);
+        g.add_edge(0, 3, 5);
+        g.add_edge(1, 2, 1);
+        g.add_edge(2, 3, 1);
+        g
+    }
+
+    #[test]
+    fn push_increase_raises_reverse_priority() {
+        let mut queue = PriorityQueue::new();
+        queue.push_increase(2, Reverse(10));
+        queue.push_increase(3, Reverse(5));
+        queue.push_increase(2, Reverse(2));
+        assert_eq!(Some(&Reverse(2)), queue.get_priority(&2));
+        queue.push_increase(2, Reverse(7));
+        assert_eq!(Some(&Reverse(2)), queue.get_priority(&2));
+        assert_eq!(Some((2, Reverse(2))), queue.pop());
+    }
+
+    #[test]
+    fn cheaper_path_updates_frontier() {
+        let g = worse_then_better();
+        let expected = Some(vec![0, 1, 2, 3]);
+        assert_eq!(expected, a_star_search(&g, 0, |n| n == 3, |_| 0));
+        assert_eq!(expected, a_star_deterministic(&g, 0, |n| n == 3, |_| 0));
+        assert_eq!(
+            expected,
+            SearchContext::new().a_star_search(&g, 0, |n| n == 3, |_| 0)
+        );
+        assert_eq!(Some((3, 3)), nearest(&g, 0, |n| n == 3));
+
+        let recorder = ExpansionRecorder::new(&g);
+        a_star_search(&recorder, 0, |n| n == 3, |_| 0);
+        let expanded_2 = recorder
+            .log()
+            .iter()
+            .filter(|call| matches!(call, GraphCall::OutEdges { node: 2, .. }))
+            .count();
+        assert_eq!(1, expanded_2);
+    }
+
+    #[test]
     fn a_star_search_works() {
         // This ignores the heuristic and makes this equivalent to djikstra's
         fn h(_node: usize) -> i32 {
-            return 0;
+            0
         }
         assert_eq!(
             Some(vec![1, 2, 3]),
@@ -267,11 +2259,480 @@ mod tests {
         );
     }
 
+    #[test]
+    fn a_star_stream_matches_a_star_search() {
+        let full = a_star_search(Cycles {}, 1, |n| n == 10, |_| 0);
+        let streamed =
+            a_star_stream(Cycles {}, 1, |n| n == 10, |_| 0).map(|p| p.collect::<Vec<_>>());
+        assert_eq!(Some(vec![1, 3, 4, 10]), streamed);
+        assert_eq!(full, streamed);
+    }
+
+    #[test]
+    fn a_star_heuristic_goal_reaches_zero_sum() {
+        let puzzle = RingPuzzle {
+            spaces: vec![-3, 7, -9, 4, -8, 1],
+        };
+        let start = RingState {
+            position: 0,
+            sum: 10,
+        };
+        let path = a_star_heuristic_goal(&puzzle, start, |s| s.sum.unsigned_abs() as usize)
+            .expect("the ring can reach a sum of zero");
+        assert_eq!(Some(&start), path.first());
+        assert_eq!(0, path.last().unwrap().sum);
+        assert_eq!(
+            path,
+            a_star_search(
+                &puzzle,
+                start,
+                |s| s.sum == 0,
+                |s| s.sum.unsigned_abs() as usize
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn a_star_search_path_goal_sees_path() {
+        assert_eq!(
+            Some(vec![1, 3, 4, 10]),
+            a_star_search_path_goal(Cycles {}, 1, |path| path.len() == 4, |_| 0)
+        );
+        assert_eq!(
+            None,
+            a_star_search_path_goal(Ex::new(), 1, |path| path.len() == 4, |_| 0)
+        );
+    }
+
+    #[test]
+    fn a_star_checked_rejects_negative_heuristic() {
+        assert_eq!(
+            Err(SearchError::NegativeHeuristic(2)),
+            a_star_checked(Ex::new(), 1, |n| n == 3, |n| if n == 2 { -1 } else { 0 })
+        );
+        assert_eq!(
+            Ok(Some(vec![1, 2, 3])),
+            a_star_checked(Ex::new(), 1, |n| n == 3, |n| 3 - n as i32)
+        );
+    }
+
+    #[test]
+    fn a_star_labeled_on_grid() {
+        let mut grid = GridGraph::new(5, 5);
+        grid.block((1, 0));
+        grid.block((1, 1));
+        grid.block((1, 2));
+        let end = (2, 0);
+        let manhattan = |(x, y): (usize, usize)| x.abs_diff(end.0) + y.abs_diff(end.1);
+        let labels = a_star_labeled(&grid, (0, 0), |n| n == end, manhattan).unwrap();
+
+        let path: Vec<_> = labels.iter().map(|label| label.node).collect();
+        assert_eq!(
+            a_star_search(&grid, (0, 0), |n| n == end, manhattan),
+            Some(path)
+        );
+        assert!(labels.windows(2).all(|w| w[0].g < w[1].g));
+        let total = labels.last().unwrap().g;
+        assert_eq!(8, total);
+        // The wall makes the heuristic loose at the start, and it is exact past the wall.
+        assert_eq!(2, labels[0].h);
+        for label in &labels[4..] {
+            assert_eq!(total - label.g, label.h);
+        }
+    }
+
+    /// `Cycles`, claiming to have `hint` nodes.
+    struct Hinted {
+        hint: Option<usize>,
+    }
+
+    impl VGraph for Hinted {
+        type Node = usize;
+
+        type Dist = usize;
+
+        fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+            Cycles {}.out_edges(node)
+        }
+
+        fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+            Cycles {}.dist(from, to)
+        }
+
+        fn size_hint(&self) -> Option<usize> {
+            self.hint
+        }
+    }
+
+    #[test]
+    fn size_hint_does_not_change_results() {
+        assert_eq!(None, Cycles {}.size_hint());
+        for hint in [None, Some(0), Some(10), Some(100_000), Some(usize::MAX)] {
+            let g = Hinted { hint };
+            assert_eq!(hint, g.size_hint());
+            assert_eq!(hint, ExpansionRecorder::new(&g).size_hint());
+            for end in 1..=10 {
+                assert_eq!(
+                    a_star_search(Cycles {}, 1, |n| n == end, |_| 0),
+                    a_star_search(&g, 1, |n| n == end, |_| 0)
+                );
+            }
+            let mut expected: Vec<_> = dijkstra_iter(Cycles {}, 7).collect();
+            let mut hinted: Vec<_> = dijkstra_iter(&g, 7).collect();
+            expected.sort();
+            hinted.sort();
+            assert_eq!(expected, hinted);
+        }
+    }
+
+    /// Every node below 1000 has ten children, only those ending in 0 have children of their
+    /// own. Knows its dead ends without listing their neighbors.
+    struct MostlyLeaves {
+        out_edges_calls: Cell<usize>,
+    }
+
+    impl VGraph for MostlyLeaves {
+        type Node = u32;
+
+        type Dist = u32;
+
+        fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+            self.out_edges_calls.set(self.out_edges_calls.get() + 1);
+            if self.is_terminal(node) {
+                return vec![];
+            }
+            (0..10).map(|i| node * 10 + i + 1).collect()
+        }
+
+        fn dist(&self, _from: Self::Node, _to: Self::Node) -> Self::Dist {
+            1
+        }
+
+        fn is_terminal(&self, node: Self::Node) -> bool {
+            node >= 1000 || (node != 0 && node % 10 != 0)
+        }
+    }
+
+    #[test]
+    fn skipping_terminals_saves_out_edges_calls() {
+        let g = MostlyLeaves {
+            out_edges_calls: Cell::new(0),
+        };
+        let is_end = |n| n == 1105;
+        let path = a_star_search(&g, 0, is_end, |_| 0);
+        let plain_calls = g.out_edges_calls.replace(0);
+
+        assert_eq!(path, a_star_skip_terminals(&g, 0, is_end, |_| 0));
+        assert_eq!(Some(vec![0, 10, 110, 1105]), path);
+        let skipping_calls = g.out_edges_calls.get();
+        assert!(
+            skipping_calls * 5 < plain_calls,
+            "{skipping_calls} calls skipping, {plain_calls} without"
+        );
+
+        // A dead end that is a goal is still found.
+        assert_eq!(
+            Some(vec![0, 10, 105]),
+            a_star_skip_terminals(&g, 0, |n| n == 105, |_| 0)
+        );
+        assert!(Ex::new().is_terminal(3));
+        assert!(!Ex::new().is_terminal(1));
+    }
+
+    #[test]
+    fn a_star_search_edges_returns_moves() {
+        let puzzle = RingPuzzle {
+            spaces: vec![-3, 7, -9, 4, -8, 1],
+        };
+        let start = RingState {
+            position: 0,
+            sum: 10,
+        };
+        let moves = a_star_search_edges(&puzzle, start, |s| s.sum == 0, |_| 0).unwrap();
+        let path = a_star_search(&puzzle, start, |s| s.sum == 0, |_| 0).unwrap();
+        assert_eq!(path.len() - 1, moves.len());
+
+        let mut replayed = vec![start];
+        for m in moves {
+            let cur = *replayed.last().unwrap();
+            replayed.push(match m {
+                "left" => puzzle.left(cur),
+                "right" => puzzle.right(cur),
+                other => panic!("unexpected move {other}"),
+            });
+        }
+        assert_eq!(path, replayed);
+    }
+
+    #[test]
+    fn apply_actions_replays_moves() {
+        let puzzle = RingPuzzle {
+            spaces: vec![-3, 7, -9, 4, -8, 1],
+        };
+        let start = RingState {
+            position: 0,
+            sum: 10,
+        };
+        let moves = a_star_search_edges(&puzzle, start, |s| s.sum == 0, |_| 0).unwrap();
+        let path = a_star_search(&puzzle, start, |s| s.sum == 0, |_| 0).unwrap();
+        assert_eq!(Some(path), apply_actions(&puzzle, start, &moves));
+
+        let right = puzzle.right(start);
+        assert_eq!(
+            Some(vec![start, right, puzzle.left(right)]),
+            apply_actions(&puzzle, start, &["right", "left"])
+        );
+        assert_eq!(Some(vec![start]), apply_actions(&puzzle, start, &[]));
+        assert_eq!(None, apply_actions(&puzzle, start, &["right", "up"]));
+        // No moves are left once the sum goes negative.
+        let stuck = RingState {
+            position: 0,
+            sum: -1,
+        };
+        assert_eq!(None, apply_actions(&puzzle, stuck, &["left"]));
+    }
+
+    #[test]
+    fn a_star_avoiding_routes_around_forbidden() {
+        let is_end = |n| n == 1;
+        assert_eq!(
+            Some(vec![4, 5, 1]),
+            a_star_avoiding(Cycles {}, 4, is_end, |_| 0, &HashSet::from([10]))
+        );
+        assert_eq!(
+            Some(vec![4, 10, 1]),
+            a_star_avoiding(Cycles {}, 4, is_end, |_| 0, &HashSet::from([5]))
+        );
+        assert_eq!(
+            None,
+            a_star_avoiding(Cycles {}, 4, is_end, |_| 0, &HashSet::from([5, 10]))
+        );
+    }
+
+    #[test]
+    fn a_star_within_stays_in_window() {
+        let mut g = GridGraph::new(8, 8);
+        for x in 0..6 {
+            g.block((x, 4));
+        }
+        let left = |(x, _): (usize, usize)| x < 4;
+        let manhattan = |(x, y): (usize, usize)| x.abs_diff(0) + y.abs_diff(7);
+
+        // Going around the wall leaves the window.
+        let around = a_star_search(&g, (0, 0), |n| n == (0, 7), manhattan).unwrap();
+        assert!(around.iter().any(|&cell| !left(cell)));
+        assert_eq!(
+            None,
+            a_star_within(&g, (0, 0), |n| n == (0, 7), manhattan, left)
+        );
+
+        let path = a_star_within(&g, (0, 0), |n| n == (3, 3), |_| 0, left).unwrap();
+        assert_eq!(7, path.len());
+        assert!(path.iter().all(|&cell| left(cell)));
+        assert_eq!(
+            None,
+            a_star_within(&g, (0, 0), |n| n == (5, 0), |_| 0, left)
+        );
+        assert_eq!(None, a_star_within(&g, (5, 0), |_| true, |_| 0, left));
+    }
+
+    #[test]
+    fn a_star_avoiding_forbidden_start_or_goal() {
+        assert_eq!(
+            None,
+            a_star_avoiding(Cycles {}, 1, |n| n == 3, |_| 0, &HashSet::from([1]))
+        );
+        assert_eq!(
+            None,
+            a_star_avoiding(Cycles {}, 1, |n| n == 3, |_| 0, &HashSet::from([3]))
+        );
+    }
+
     #[test]
     fn a_star_search_for_non_path_terminates() {
         assert_eq!(None, a_star_search(Cycles {}, 1, |n| n == 33, |_| 0));
     }
 
+    #[test]
+    fn dijkstra_timed_finishes_before_deadline() {
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let distances = dijkstra_timed(Cycles {}, 1, deadline);
+        assert_eq!(
+            HashMap::from([(1, 0), (2, 1), (3, 1), (6, 2), (4, 4), (5, 4), (10, 5)]),
+            distances
+        );
+    }
+
+    #[test]
+    fn dijkstra_timed_out_of_time_settles_nothing() {
+        assert!(dijkstra_timed(Cycles {}, 1, Instant::now()).is_empty());
+    }
+
+    #[test]
+    fn dijkstra_until_returns_partial_distances() {
+        let full = dijkstra_until(Cycles {}, 1, || false).dist;
+        for steps in 0..full.len() {
+            let mut left = steps;
+            let partial = dijkstra_until(Cycles {}, 1, || {
+                let stop = left == 0;
+                left = left.saturating_sub(1);
+                stop
+            })
+            .dist;
+            assert_eq!(steps, partial.len());
+            for (node, dist) in partial {
+                assert_eq!(full[&node], dist);
+            }
+        }
+    }
+
+    #[test]
+    fn predecessor_path_walks_back_from_the_end() {
+        let found = a_star_core(
+            Cycles {},
+            1,
+            |_, n| n == 10,
+            |_| 0,
+            |from_start, h, _| Reverse(from_start + h),
+        )
+        .unwrap();
+        let mut path: Vec<usize> = PredecessorPath::new(&found.prev, 10).collect();
+        assert_eq!(vec![10, 4, 3, 1], path);
+        path.reverse();
+        assert_eq!(back_track(&found.prev, 10), path);
+
+        assert_eq!(
+            vec![7],
+            PredecessorPath::new(&found.prev, 7).collect::<Vec<_>>()
+        );
+        let mut steps = PredecessorPath::new(&found.prev, 3);
+        assert_eq!(
+            (Some(3), Some(1), None, None),
+            (steps.next(), steps.next(), steps.next(), steps.next())
+        );
+    }
+
+    #[test]
+    fn a_star_anytime_completes_in_time() {
+        let deadline = Instant::now() + Duration::from_secs(60);
+        assert_eq!(
+            Some(AnytimePath::Complete(
+                a_star_search(Cycles {}, 1, |n| n == 10, |_| 0).unwrap()
+            )),
+            a_star_anytime(Cycles {}, 1, |n| n == 10, |_| 0, deadline)
+        );
+        assert_eq!(
+            None,
+            a_star_anytime(Cycles {}, 1, |n| n == 7, |_| 0, deadline)
+        );
+    }
+
+    #[test]
+    fn a_star_anytime_returns_partial_path() {
+        let g = GridGraph::new(30, 30);
+        let end = (29, 29);
+        let manhattan = |(x, y): (usize, usize)| x.abs_diff(end.0) + y.abs_diff(end.1);
+        for explored in 1..20 {
+            let mut left = explored;
+            let Some(AnytimePath::Partial(path)) = a_star_until(
+                &g,
+                (0, 0),
+                |n| n == end,
+                manhattan,
+                || {
+                    left -= 1;
+                    left == 0
+                },
+            ) else {
+                panic!("the search should run out of time");
+            };
+            assert_eq!(Some(&(0, 0)), path.first());
+            assert!(is_valid_path(&g, &path));
+            if explored == 1 {
+                assert_eq!(vec![(0, 0)], path);
+            } else {
+                assert!(manhattan(*path.last().unwrap()) < manhattan((0, 0)));
+            }
+        }
+
+        // Out of time before exploring anything but the start.
+        assert_eq!(
+            Some(AnytimePath::Partial(vec![(0, 0)])),
+            a_star_anytime(&g, (0, 0), |n| n == end, manhattan, Instant::now())
+        );
+    }
+
+    #[test]
+    fn distances_to_goal() {
+        let g = crate::bench::random_graph(25, 3, 2);
+        let to_goal = distances_to(&g, 4);
+        for node in 0..25 {
+            assert_eq!(distance(&g, node, 4), to_goal.get(&node).copied());
+        }
+    }
+
+    #[test]
+    fn dijkstra_iter_settles_in_distance_order() {
+        let distances: Vec<usize> = dijkstra_iter(Cycles {}, 1).map(|(_, d)| d).collect();
+        assert_eq!(7, distances.len());
+        assert!(distances.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn dijkstra_tree_iter_builds_a_tree() {
+        for start in 1..=10 {
+            let mut settled: HashMap<usize, usize> = HashMap::new();
+            for (node, dist, prev) in dijkstra_tree_iter(Cycles {}, start) {
+                match prev {
+                    None => {
+                        assert_eq!(start, node);
+                        assert_eq!(0, dist);
+                    }
+                    Some(prev) => {
+                        assert!(Cycles {}.out_edges(prev).contains(&node));
+                        assert_eq!(settled[&prev] + Cycles {}.dist(prev, node), dist);
+                    }
+                }
+                assert!(settled.insert(node, dist).is_none());
+            }
+            let expected: HashMap<usize, usize> = dijkstra_iter(Cycles {}, start).collect();
+            assert_eq!(expected, settled);
+        }
+    }
+
+    #[test]
+    fn dense_distances_include_unreachable() {
+        let distances = shortest_distances_dense(Cycles {}, 1, &[1, 3, 7, 10]);
+        assert_eq!(
+            HashMap::from([(1, Some(0)), (3, Some(1)), (7, None), (10, Some(5))]),
+            distances
+        );
+        assert!(shortest_distances_dense(Cycles {}, 1, &[]).is_empty());
+    }
+
+    #[test]
+    fn goals_by_distance_nearest_first() {
+        let even = |n: usize| n.is_multiple_of(2);
+        let goals: Vec<_> = goals_by_distance(Cycles {}, 1, even).take(2).collect();
+        assert_eq!(vec![(2, vec![1, 2], 1), (6, vec![1, 2, 6], 2)], goals);
+
+        let all: Vec<_> = goals_by_distance(Cycles {}, 1, even).collect();
+        assert_eq!(4, all.len());
+        assert_eq!(Some(&(10, vec![1, 3, 4, 10], 5)), all.last());
+        assert!(all.windows(2).all(|w| w[0].2 <= w[1].2));
+        assert_eq!(0, goals_by_distance(Cycles {}, 1, |n| n > 20).count());
+    }
+
+    #[test]
+    fn nearest_finds_closest_match() {
+        let even = |n: usize| n.is_multiple_of(2);
+        assert_eq!(Some((2, 1)), nearest(Cycles {}, 1, even));
+        assert_eq!(Some((4, 3)), nearest(Cycles {}, 3, even));
+        assert_eq!(Some((8, 0)), nearest(Cycles {}, 8, even));
+        assert_eq!(None, nearest(Cycles {}, 1, |n| n > 20));
+    }
+
     #[test]
     fn bfs_all_paths_test() {
         assert_eq!(
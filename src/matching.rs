@@ -0,0 +1,163 @@
+use crate::VGraph;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// A maximum matching between `left` and `right`, using the Hopcroft-Karp algorithm.
+///
+/// Only edges from a `left` node to a `right` node are used. Returns the matched pairs as
+/// `(left, right)`, in the order of `left`. Each round finds a set of shortest augmenting paths
+/// with a breadth first search and applies them with depth first searches, so the matching is
+/// found in about the square root of the number of nodes rounds.
+pub fn bipartite_matching<G>(g: G, left: &[G::Node], right: &[G::Node]) -> Vec<(G::Node, G::Node)>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    let right_set: HashSet<G::Node> = right.iter().copied().collect();
+    let adjacent: HashMap<G::Node, Vec<G::Node>> = left
+        .iter()
+        .map(|&l| {
+            let mut targets = g.out_edges(l);
+            targets.retain(|r| right_set.contains(r));
+            (l, targets)
+        })
+        .collect();
+
+    let mut matching = Matching {
+        adjacent: &adjacent,
+        left_pair: HashMap::new(),
+        right_pair: HashMap::new(),
+        layer: HashMap::new(),
+        free_layer: None,
+    };
+    while matching.build_layers(left) {
+        for &l in left {
+            if !matching.left_pair.contains_key(&l) {
+                matching.augment(l);
+            }
+        }
+    }
+
+    left.iter()
+        .filter_map(|l| matching.left_pair.get(l).map(|&r| (*l, r)))
+        .collect()
+}
+
+struct Matching<'a, N> {
+    adjacent: &'a HashMap<N, Vec<N>>,
+    left_pair: HashMap<N, N>,
+    right_pair: HashMap<N, N>,
+    // The breadth first layer of each left node reached this round.
+    layer: HashMap<N, usize>,
+    // The layer at which a free right node was first reached.
+    free_layer: Option<usize>,
+}
+
+impl<N> Matching<'_, N>
+where
+    N: Hash + Eq + Copy,
+{
+    /// Layers the left nodes by their alternating distance from the free left nodes. Returns
+    /// false when no augmenting path exists, so the matching is maximum.
+    fn build_layers(&mut self, left: &[N]) -> bool {
+        self.layer.clear();
+        self.free_layer = None;
+        let mut to_visit = VecDeque::new();
+        for &l in left {
+            if !self.left_pair.contains_key(&l) {
+                self.layer.insert(l, 0);
+                to_visit.push_back(l);
+            }
+        }
+
+        while let Some(l) = to_visit.pop_front() {
+            let next_layer = self.layer[&l] + 1;
+            if self.free_layer.is_some_and(|free| next_layer > free) {
+                break;
+            }
+            for r in &self.adjacent[&l] {
+                match self.right_pair.get(r) {
+                    None => {
+                        self.free_layer.get_or_insert(next_layer);
+                    }
+                    Some(&paired) => {
+                        if let Entry::Vacant(entry) = self.layer.entry(paired) {
+                            entry.insert(next_layer);
+                            to_visit.push_back(paired);
+                        }
+                    }
+                }
+            }
+        }
+        self.free_layer.is_some()
+    }
+
+    /// Looks for a shortest augmenting path from `l` along the layers, flipping it into the
+    /// matching if found.
+    fn augment(&mut self, l: N) -> bool {
+        let Some(&cur_layer) = self.layer.get(&l) else {
+            return false;
+        };
+        for &r in &self.adjacent[&l] {
+            let extends = match self.right_pair.get(&r) {
+                None => self.free_layer == Some(cur_layer + 1),
+                Some(&paired) => {
+                    self.layer.get(&paired) == Some(&(cur_layer + 1)) && self.augment(paired)
+                }
+            };
+            if extends {
+                self.left_pair.insert(l, r);
+                self.right_pair.insert(r, l);
+                return true;
+            }
+        }
+        // No path through `l` this round, keep other searches from trying it again.
+        self.layer.remove(&l);
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AdjacencyListGraph;
+
+    #[test]
+    fn assigns_workers_to_jobs() {
+        // Workers 0..5 and jobs 10..15, the greedy choice for worker 0 blocks worker 1.
+        let mut g = AdjacencyListGraph::new();
+        for (worker, job) in [
+            (0, 10),
+            (0, 11),
+            (1, 10),
+            (2, 11),
+            (2, 12),
+            (3, 12),
+            (3, 13),
+            (4, 12),
+        ] {
+            g.add_edge(worker, job, ());
+        }
+        let left = [0, 1, 2, 3, 4];
+        let right = [10, 11, 12, 13, 14];
+        let matching = bipartite_matching(&g, &left, &right);
+        assert_eq!(4, matching.len());
+
+        let mut used_right = HashSet::new();
+        for &(worker, job) in &matching {
+            assert!(g.out_edges(worker).contains(&job));
+            assert!(used_right.insert(job));
+        }
+    }
+
+    #[test]
+    fn perfect_matching() {
+        let mut g = AdjacencyListGraph::new();
+        for (l, r) in [(0, 3), (0, 4), (1, 3), (2, 4), (2, 5)] {
+            g.add_edge(l, r, ());
+        }
+        let matching = bipartite_matching(&g, &[0, 1, 2], &[3, 4, 5]);
+        assert_eq!(vec![(0, 4), (1, 3), (2, 5)], matching);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{a_star_search, VGraph};
+use num::traits::Zero;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Caches the result of `compute` for each node, running it at most once per node.
+///
+/// Takes `&self`, so it can be called from inside the `Fn` closures searches take, such as an
+/// expensive heuristic:
+///
+/// ```
+/// use vgraph::{a_star_search, FnGraph, NodeMemo};
+///
+/// let line = FnGraph::new(|n: u32| vec![n + 1], |_, _| 1);
+/// let to_goal = NodeMemo::new(|n: u32| 10 - n.min(10));
+/// let path = a_star_search(line, 0, |n| n == 10, |n| to_goal.get_or_compute(n));
+/// assert_eq!(Some((0..=10).collect()), path);
+/// assert_eq!(11, to_goal.len());
+/// ```
+pub struct NodeMemo<N, V, F> {
+    compute: F,
+    values: RefCell<HashMap<N, V>>,
+}
+
+impl<N, V, F> NodeMemo<N, V, F>
+where
+    N: Hash + Eq + Copy,
+    V: Clone,
+    F: Fn(N) -> V,
+{
+    pub fn new(compute: F) -> Self {
+        NodeMemo {
+            compute,
+            values: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// The value for `node`, computing it the first time the node is asked for.
+    pub fn get_or_compute(&self, node: N) -> V {
+        if let Some(value) = self.values.borrow().get(&node) {
+            return value.clone();
+        }
+        // Computed without holding the borrow, so `compute` may itself use the memo.
+        let value = (self.compute)(node);
+        self.values.borrow_mut().insert(node, value.clone());
+        value
+    }
+
+    /// Number of nodes with a cached value.
+    pub fn len(&self) -> usize {
+        self.values.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.borrow().is_empty()
+    }
+
+    /// Forgets every cached value.
+    pub fn clear(&self) {
+        self.values.borrow_mut().clear();
+    }
+}
+
+/// A* search that evaluates `is_end` at most once per node.
+///
+/// `a_star_search` checks a node each time it comes off the frontier, which happens more than
+/// once when a heuristic that is not consistent lets a cheaper path to it turn up later. Worth
+/// it when `is_end` is expensive. The answer is cached, so `is_end` must always give the same
+/// answer for the same node.
+pub fn a_star_cached_goal<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    let goals = NodeMemo::new(is_end);
+    a_star_search(g, start, |n| goals.get_or_compute(n), heuristic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::Cycles;
+    use crate::AdjacencyListGraph;
+    use std::cell::Cell;
+
+    #[test]
+    fn computes_once_per_node() {
+        let calls = Cell::new(0);
+        let memo = NodeMemo::new(|n: usize| {
+            calls.set(calls.get() + 1);
+            n * 2
+        });
+        assert!(memo.is_empty());
+        assert_eq!(6, memo.get_or_compute(3));
+        assert_eq!(6, memo.get_or_compute(3));
+        assert_eq!(8, memo.get_or_compute(4));
+        assert_eq!(2, calls.get());
+        assert_eq!(2, memo.len());
+
+        memo.clear();
+        assert_eq!(6, memo.get_or_compute(3));
+        assert_eq!(3, calls.get());
+    }
+
+    #[test]
+    fn caches_a_heuristic() {
+        let calls = Cell::new(0);
+        let memo = NodeMemo::new(|_: usize| {
+            calls.set(calls.get() + 1);
+            0
+        });
+        let path = a_star_search(Cycles {}, 1, |n| n == 10, |n| memo.get_or_compute(n));
+        assert_eq!(Some(vec![1, 3, 4, 10]), path);
+        assert_eq!(memo.len(), calls.get());
+    }
+
+    #[test]
+    fn goal_checked_once_per_node() {
+        // The heuristic is not consistent at 1, so 2 comes off the frontier a second time once
+        // the cheaper path through 1 is found.
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(0, 1, 2);
+        g.add_edge(0, 3, 1);
+        g.add_edge(1, 2, 1);
+        g.add_edge(3, 2, 3);
+        g.add_edge(2, 4, 5);
+        let heuristic = |n| if n == 1 { 3 } else { 0 };
+
+        let calls = RefCell::new(HashMap::new());
+        let is_end = |n: u32| {
+            *calls.borrow_mut().entry(n).or_insert(0) += 1;
+            n == 4
+        };
+        assert_eq!(
+            Some(vec![0, 1, 2, 4]),
+            a_star_search(&g, 0, is_end, heuristic)
+        );
+        assert_eq!(2, calls.borrow()[&2]);
+
+        calls.borrow_mut().clear();
+        assert_eq!(
+            Some(vec![0, 1, 2, 4]),
+            a_star_cached_goal(&g, 0, is_end, heuristic)
+        );
+        assert!(calls.borrow().values().all(|&count| count == 1));
+        assert_eq!(5, calls.borrow().len());
+    }
+}
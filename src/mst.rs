@@ -0,0 +1,318 @@
+use crate::{UndirectedVGraph, VGraph};
+use priority_queue::PriorityQueue;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::Sub;
+
+/// An edge between nodes numbered from zero, as `(from, to, dist, id)`. The id is the edge's
+/// index in the list one level of contraction up.
+type IndexedEdge<D> = (usize, usize, D, usize);
+
+/// Minimum spanning forest over `nodes` using Prim's algorithm.
+///
+/// Edges leading outside of `nodes` are ignored. Returns the tree edges as `(from, to, dist)`,
+/// where `from` was already in the tree when the edge was added.
+pub fn minimum_spanning_tree<G>(g: G, nodes: &[G::Node]) -> Vec<(G::Node, G::Node, G::Dist)>
+where
+    G: UndirectedVGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Ord + Copy,
+{
+    spanning_forest(g, nodes)
+}
+
+/// `minimum_spanning_tree` for graphs that are known to be symmetric without saying so in
+/// their type.
+pub(crate) fn spanning_forest<G>(g: G, nodes: &[G::Node]) -> Vec<(G::Node, G::Node, G::Dist)>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Ord + Copy,
+{
+    let allowed: HashSet<G::Node> = nodes.iter().copied().collect();
+    let mut in_tree: HashSet<G::Node> = HashSet::new();
+    let mut tree = Vec::new();
+
+    // Grow a separate tree from every node not yet reached, so disconnected nodes still get
+    // spanned.
+    for &root in nodes {
+        if !in_tree.insert(root) {
+            continue;
+        }
+
+        let mut to_explore = PriorityQueue::new();
+        let mut parent: HashMap<G::Node, G::Node> = HashMap::new();
+        let mut cur = root;
+        loop {
+            for next in g.out_edges(cur) {
+                if !allowed.contains(&next) || in_tree.contains(&next) {
+                    continue;
+                }
+                let dist = g.dist(cur, next);
+                let improves = to_explore
+                    .get_priority(&next)
+                    .map(|Reverse(best)| dist < *best)
+                    .unwrap_or(true);
+                if improves {
+                    to_explore.push(next, Reverse(dist));
+                    parent.insert(next, cur);
+                }
+            }
+
+            let Some((next, Reverse(dist))) = to_explore.pop() else {
+                break;
+            };
+            in_tree.insert(next);
+            tree.push((parent[&next], next, dist));
+            cur = next;
+        }
+    }
+
+    tree
+}
+
+/// Minimum cost arborescence over `nodes` rooted at `root`, the directed counterpart of a
+/// minimum spanning tree, using the Chu-Liu/Edmonds algorithm.
+///
+/// Every node of `nodes` reachable from `root` without leaving `nodes` gets exactly one edge
+/// leading into it, and following them backward from any node ends at `root`. Nodes that can
+/// not be reached are left out. Returns the edges as `(from, to, dist)`.
+///
+/// Each node first takes its cheapest edge in. Any cycle those edges form is contracted into a
+/// single node, with the edges into it made cheaper by what they would save, and the contracted
+/// graph is solved the same way before the cycle is opened back up. Costs `O(nodes * edges)`.
+pub fn min_arborescence<G>(
+    g: G,
+    root: G::Node,
+    nodes: &[G::Node],
+) -> Vec<(G::Node, G::Node, G::Dist)>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Ord + Copy + Sub<Output = G::Dist>,
+{
+    let allowed: HashSet<G::Node> = nodes.iter().copied().collect();
+    if !allowed.contains(&root) {
+        return Vec::new();
+    }
+    // Number the reachable nodes, the root first.
+    let mut reached = vec![root];
+    let mut index: HashMap<G::Node, usize> = HashMap::from([(root, 0)]);
+    let mut i = 0;
+    while let Some(&cur) = reached.get(i) {
+        for next in g.out_edges(cur) {
+            if allowed.contains(&next) && !index.contains_key(&next) {
+                index.insert(next, reached.len());
+                reached.push(next);
+            }
+        }
+        i += 1;
+    }
+
+    let mut edges: Vec<IndexedEdge<G::Dist>> = Vec::new();
+    for (from, &cur) in reached.iter().enumerate() {
+        for next in g.out_edges(cur) {
+            if let Some(&to) = index.get(&next) {
+                edges.push((from, to, g.dist(cur, next), 0));
+            }
+        }
+    }
+    contract_cycles(reached.len(), 0, &edges)
+        .into_iter()
+        .map(|id| {
+            let (from, to, dist, _) = edges[id];
+            (reached[from], reached[to], dist)
+        })
+        .collect()
+}
+
+/// The positions in `edges` of a minimum arborescence over `0..num_nodes`, every node of
+/// which must be reachable from `root`.
+fn contract_cycles<D>(num_nodes: usize, root: usize, edges: &[IndexedEdge<D>]) -> Vec<usize>
+where
+    D: Ord + Copy + Sub<Output = D>,
+{
+    let mut cheapest_in: Vec<Option<usize>> = vec![None; num_nodes];
+    for (id, &(from, to, dist, _)) in edges.iter().enumerate() {
+        if from != to && to != root && cheapest_in[to].is_none_or(|best| dist < edges[best].2) {
+            cheapest_in[to] = Some(id);
+        }
+    }
+    let parent = |node: usize| {
+        let id = cheapest_in[node].expect("Every node but the root should have an edge in.");
+        edges[id].0
+    };
+
+    // Walk up from each node, a walk that runs into itself has found a cycle.
+    let mut component: Vec<Option<usize>> = vec![None; num_nodes];
+    let mut walked_from: Vec<Option<usize>> = vec![None; num_nodes];
+    let mut on_cycle = vec![false; num_nodes];
+    let mut num_components = 0;
+    for start in 0..num_nodes {
+        let mut cur = start;
+        while cur != root && walked_from[cur].is_none() {
+            walked_from[cur] = Some(start);
+            cur = parent(cur);
+        }
+        if cur != root && walked_from[cur] == Some(start) && component[cur].is_none() {
+            let mut member = cur;
+            loop {
+                component[member] = Some(num_components);
+                on_cycle[member] = true;
+                member = parent(member);
+                if member == cur {
+                    break;
+                }
+            }
+            num_components += 1;
+        }
+    }
+    let chosen = |node: usize| cheapest_in[node];
+    if num_components == 0 {
+        return (0..num_nodes).filter_map(chosen).collect();
+    }
+
+    let component: Vec<usize> = component
+        .into_iter()
+        .map(|c| {
+            c.unwrap_or_else(|| {
+                num_components += 1;
+                num_components - 1
+            })
+        })
+        .collect();
+    // Entering a cycle at a node replaces that node's edge from within the cycle.
+    let contracted: Vec<IndexedEdge<D>> = edges
+        .iter()
+        .enumerate()
+        .filter(|&(_, &(from, to, _, _))| component[from] != component[to])
+        .map(|(id, &(from, to, dist, _))| {
+            let dist = if on_cycle[to] {
+                dist - edges[cheapest_in[to].unwrap()].2
+            } else {
+                dist
+            };
+            (component[from], component[to], dist, id)
+        })
+        .collect();
+
+    let mut tree: Vec<usize> = contract_cycles(num_components, component[root], &contracted)
+        .into_iter()
+        .map(|id| contracted[id].3)
+        .collect();
+    let entered: HashSet<usize> = tree.iter().map(|&id| edges[id].1).collect();
+    tree.extend(
+        (0..num_nodes)
+            .filter(|&node| on_cycle[node] && !entered.contains(&node))
+            .filter_map(chosen),
+    );
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::random_graph;
+    use crate::{AdjacencyListGraph, SymmetricWrapper};
+
+    fn undirected(
+        edges: &[(usize, usize, u32)],
+    ) -> SymmetricWrapper<AdjacencyListGraph<usize, u32>> {
+        let mut g = AdjacencyListGraph::new();
+        for &(a, b, d) in edges {
+            g.add_edge(a, b, d);
+        }
+        SymmetricWrapper::new(g)
+    }
+
+    #[test]
+    fn spans_with_minimum_cost() {
+        let g = undirected(&[(1, 2, 1), (2, 3, 2), (1, 3, 5), (3, 4, 1), (2, 4, 4)]);
+        let tree = minimum_spanning_tree(&g, &[1, 2, 3, 4]);
+        assert_eq!(3, tree.len());
+        assert_eq!(4, tree.iter().map(|&(_, _, d)| d).sum::<u32>());
+    }
+
+    #[test]
+    fn spans_disconnected_nodes_as_a_forest() {
+        let g = undirected(&[(1, 2, 1), (3, 4, 2)]);
+        let tree = minimum_spanning_tree(&g, &[1, 2, 3, 4]);
+        assert_eq!(vec![(1, 2, 1), (3, 4, 2)], tree);
+    }
+
+    #[test]
+    fn arborescence_opens_the_cheap_cycle() {
+        // 1 and 2 are each other's cheapest way in.
+        let mut g = AdjacencyListGraph::new();
+        for (from, to, dist) in [
+            (0, 1, 10),
+            (0, 2, 12),
+            (1, 2, 1),
+            (2, 1, 1),
+            (2, 3, 2),
+            (3, 1, 5),
+        ] {
+            g.add_edge(from, to, dist);
+        }
+        let mut tree = min_arborescence(&g, 0, &[0, 1, 2, 3]);
+        tree.sort();
+        assert_eq!(vec![(0, 1, 10), (1, 2, 1), (2, 3, 2)], tree);
+
+        // Nodes the root can not reach are left out.
+        g.add_node(4);
+        g.add_edge(4, 0, 1);
+        let mut tree = min_arborescence(&g, 0, &[0, 1, 2, 3, 4]);
+        tree.sort();
+        assert_eq!(vec![(0, 1, 10), (1, 2, 1), (2, 3, 2)], tree);
+        assert!(min_arborescence(&g, 0, &[1, 2]).is_empty());
+    }
+
+    /// Cost of the cheapest arborescence over `0..n`, trying every choice of edge into each node.
+    fn brute_force(g: &AdjacencyListGraph<usize, u32>, n: usize) -> u32 {
+        let into: Vec<Vec<(usize, u32)>> = (0..n)
+            .map(|to| {
+                (0..n)
+                    .flat_map(|from| g.edges_from(from).iter().map(move |&(t, d)| (from, t, d)))
+                    .filter(|&(from, t, _)| t == to && from != to)
+                    .map(|(from, _, d)| (from, d))
+                    .collect()
+            })
+            .collect();
+        let mut best = u32::MAX;
+        let mut choice = vec![0; n];
+        loop {
+            let reaches_root = (1..n).all(|start| {
+                let mut cur = start;
+                for _ in 0..n {
+                    if cur == 0 {
+                        return true;
+                    }
+                    cur = into[cur][choice[cur]].0;
+                }
+                cur == 0
+            });
+            if reaches_root {
+                best = best.min((1..n).map(|node| into[node][choice[node]].1).sum());
+            }
+            // Count through every choice, like an odometer.
+            let Some(node) = (1..n).find(|&node| choice[node] + 1 < into[node].len()) else {
+                return best;
+            };
+            choice[node] += 1;
+            choice[1..node].fill(0);
+        }
+    }
+
+    #[test]
+    fn arborescence_matches_brute_force() {
+        for seed in 0..20 {
+            let g = random_graph(6, 2, seed);
+            let nodes: Vec<usize> = (0..6).collect();
+            let tree = min_arborescence(&g, 0, &nodes);
+            assert_eq!(5, tree.len());
+            let cost: u32 = tree.iter().map(|&(_, _, d)| d).sum();
+            assert_eq!(brute_force(&g, 6), cost, "seed {seed}");
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{a_star_search, VGraph};
+use num::traits::Zero;
+use std::hash::Hash;
+use std::ops::Add;
+
+/// A graph that also charges for entering a node, such as a toll or the time spent waiting at
+/// it.
+pub trait NodeCostGraph: VGraph {
+    /// Cost of entering `node`, added to the distance of every edge leading to it.
+    fn node_cost(&self, _node: Self::Node) -> Self::Dist
+    where
+        Self::Dist: Zero,
+    {
+        Self::Dist::zero()
+    }
+}
+
+impl<G> NodeCostGraph for &G
+where
+    G: NodeCostGraph + ?Sized,
+{
+    fn node_cost(&self, node: Self::Node) -> Self::Dist
+    where
+        Self::Dist: Zero,
+    {
+        (**self).node_cost(node)
+    }
+}
+
+/// `a_star_search` where every node entered along the path adds its `node_cost`.
+///
+/// The path starts at `start`, so its cost is never charged, but the goal's is. `heuristic`
+/// must account for node costs as well to stay admissible, all zero is always safe.
+pub fn a_star_node_costs<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+) -> Option<Vec<G::Node>>
+where
+    G: NodeCostGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    a_star_search(EntryCosts { g }, start, is_end, heuristic)
+}
+
+/// Folds the cost of entering a node into the edges leading to it.
+struct EntryCosts<G> {
+    g: G,
+}
+
+impl<G> VGraph for EntryCosts<G>
+where
+    G: NodeCostGraph,
+    G::Node: Copy,
+    G::Dist: Zero + Add<Output = G::Dist>,
+{
+    type Node = G::Node;
+
+    type Dist = G::Dist;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.g.out_edges(node)
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        self.g.dist(from, to) + self.g.node_cost(to)
+    }
+
+    fn is_terminal(&self, node: Self::Node) -> bool {
+        self.g.is_terminal(node)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.g.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::Cycles;
+
+    /// `Cycles`, where entering `node` costs `cost`.
+    struct Toll {
+        node: usize,
+        cost: usize,
+    }
+
+    impl VGraph for Toll {
+        type Node = usize;
+
+        type Dist = usize;
+
+        fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+            Cycles {}.out_edges(node)
+        }
+
+        fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+            Cycles {}.dist(from, to)
+        }
+    }
+
+    impl NodeCostGraph for Toll {
+        fn node_cost(&self, node: Self::Node) -> Self::Dist {
+            if node == self.node {
+                self.cost
+            } else {
+                0
+            }
+        }
+    }
+
+    #[test]
+    fn penalized_node_is_avoided() {
+        let either = |n| n == 3 || n == 6;
+        let free = Toll { node: 3, cost: 0 };
+        assert_eq!(Some(vec![1, 3]), a_star_node_costs(&free, 1, either, |_| 0));
+        let toll = Toll { node: 3, cost: 5 };
+        assert_eq!(
+            Some(vec![1, 2, 6]),
+            a_star_node_costs(&toll, 1, either, |_| 0)
+        );
+
+        let toll = Toll { node: 5, cost: 1 };
+        assert_eq!(
+            Some(vec![4, 10, 1]),
+            a_star_node_costs(&toll, 4, |n| n == 1, |_| 0)
+        );
+        // The start is never charged.
+        assert_eq!(
+            Some(vec![5, 1]),
+            a_star_node_costs(&toll, 5, |n| n == 1, |_| 0)
+        );
+    }
+}
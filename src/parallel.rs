@@ -0,0 +1,178 @@
+//! Searches that spread work across threads with rayon.
+use crate::{a_star_search, VGraph};
+use num::traits::Zero;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::Div;
+
+/// Answers many independent shortest path queries on the same graph in parallel.
+///
+/// Each `(start, end)` query is answered as `a_star_search` with no heuristic would, and the
+/// results are returned in the same order as `queries`.
+pub fn batch_shortest_paths<G>(g: &G, queries: &[(G::Node, G::Node)]) -> Vec<Option<Vec<G::Node>>>
+where
+    G: VGraph + Sync,
+    G::Node: Hash + Eq + Ord + Copy + Send + Sync,
+    G::Dist: Zero + Ord + Copy,
+{
+    queries
+        .par_iter()
+        .map(|&(start, end)| a_star_search(g, start, |n| n == end, |_| G::Dist::zero()))
+        .collect()
+}
+
+/// Nodes waiting to be settled, grouped by their tentative distance divided by `delta`.
+type Buckets<N, D> = BTreeMap<D, HashSet<N>>;
+
+/// Edges to relax, as the node they lead to and the distance through them.
+type Requests<N, D> = Vec<(N, D)>;
+
+/// The distance from `start` to every node it reaches, found in parallel with delta-stepping.
+///
+/// Gives the same distances as running `dijkstra_iter` to completion. Nodes are grouped into
+/// buckets of width `delta` by their tentative distance. The nodes of the nearest bucket have
+/// their edges of at most `delta` relaxed all at once across threads, repeating until that
+/// bucket stays empty, and then their longer edges are relaxed.
+///
+/// A small `delta` settles few nodes per round, close to Dijkstra. A large one relaxes many
+/// edges more than once, close to Bellman-Ford. Around the average edge distance is a good
+/// start. Only pays off on large graphs where `out_edges` is expensive enough to share out.
+///
+/// Panics if `delta` is not greater than zero.
+pub fn delta_stepping<G>(g: &G, start: G::Node, delta: G::Dist) -> HashMap<G::Node, G::Dist>
+where
+    G: VGraph + Sync,
+    G::Node: Hash + Eq + Copy + Send + Sync,
+    G::Dist: Zero + Ord + Copy + Div<Output = G::Dist> + Send + Sync,
+{
+    assert!(delta > G::Dist::zero(), "delta must be greater than zero");
+    let mut tentative: HashMap<G::Node, G::Dist> = HashMap::new();
+    let mut buckets: Buckets<G::Node, G::Dist> = BTreeMap::new();
+    relax(&mut tentative, &mut buckets, delta, start, G::Dist::zero());
+
+    while let Some((&index, _)) = buckets.first_key_value() {
+        // Long edges of every node settled in this bucket, from its final distance.
+        let mut heavy: HashMap<G::Node, Requests<G::Node, G::Dist>> = HashMap::new();
+        // Short edges can land back in this bucket, so keep going until it stays empty.
+        while let Some(nodes) = buckets.remove(&index) {
+            let frontier: Vec<(G::Node, G::Dist)> = nodes
+                .into_iter()
+                .map(|node| (node, tentative[&node]))
+                .collect();
+            let requests: Vec<_> = frontier
+                .par_iter()
+                .map(|&(node, dist)| {
+                    let (light, long) = g
+                        .out_edges(node)
+                        .into_iter()
+                        .map(|next| (next, g.dist(node, next)))
+                        .partition::<Vec<_>, _>(|&(_, edge)| edge <= delta);
+                    let through = |(next, edge)| (next, dist + edge);
+                    (
+                        node,
+                        light.into_iter().map(through).collect::<Requests<_, _>>(),
+                        long.into_iter().map(through).collect::<Requests<_, _>>(),
+                    )
+                })
+                .collect();
+            for (node, light, long) in requests {
+                for (next, dist) in light {
+                    relax(&mut tentative, &mut buckets, delta, next, dist);
+                }
+                heavy.insert(node, long);
+            }
+        }
+        for (next, dist) in heavy.into_values().flatten() {
+            relax(&mut tentative, &mut buckets, delta, next, dist);
+        }
+    }
+    tentative
+}
+
+/// Moves `node` to the bucket for `dist` if that is shorter than its tentative distance.
+fn relax<N, D>(
+    tentative: &mut HashMap<N, D>,
+    buckets: &mut Buckets<N, D>,
+    delta: D,
+    node: N,
+    dist: D,
+) where
+    N: Hash + Eq + Copy,
+    D: Ord + Copy + Div<Output = D>,
+{
+    if let Some(&old) = tentative.get(&node) {
+        if dist >= old {
+            return;
+        }
+        let index = old / delta;
+        if let Some(bucket) = buckets.get_mut(&index) {
+            bucket.remove(&node);
+            if bucket.is_empty() {
+                buckets.remove(&index);
+            }
+        }
+    }
+    tentative.insert(node, dist);
+    buckets.entry(dist / delta).or_default().insert(node);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::random_graph;
+    use crate::dijkstra_iter;
+    use crate::test_graphs::Cycles;
+
+    #[test]
+    fn batch_matches_sequential() {
+        let g = random_graph(60, 3, 2);
+        let queries: Vec<(usize, usize)> = (0..60).map(|i| (i, (i * 7 + 3) % 60)).collect();
+        let sequential: Vec<_> = queries
+            .iter()
+            .map(|&(start, end)| a_star_search(&g, start, |n| n == end, |_| 0))
+            .collect();
+        assert_eq!(sequential, batch_shortest_paths(&g, &queries));
+    }
+
+    #[test]
+    fn batch_keeps_unreachable_queries() {
+        assert_eq!(
+            vec![Some(vec![1, 3, 4, 10]), None],
+            batch_shortest_paths(&Cycles {}, &[(1, 10), (1, 7)])
+        );
+    }
+
+    #[test]
+    fn delta_stepping_matches_dijkstra() {
+        for seed in 0..5 {
+            let g = random_graph(200, 4, seed);
+            let expected: HashMap<usize, u32> = dijkstra_iter(&g, 0).collect();
+            for delta in [1, 25, 100, 10_000] {
+                assert_eq!(
+                    expected,
+                    delta_stepping(&g, 0, delta),
+                    "seed {seed} delta {delta}"
+                );
+            }
+        }
+
+        let expected: HashMap<usize, usize> = dijkstra_iter(Cycles {}, 1).collect();
+        assert_eq!(expected, delta_stepping(&Cycles {}, 1, 2));
+        assert_eq!(
+            HashMap::from([
+                (7, 0),
+                (8, 1),
+                (9, 2),
+                (10, 3),
+                (1, 4),
+                (2, 5),
+                (3, 5),
+                (6, 6),
+                (4, 8),
+                (5, 8)
+            ]),
+            delta_stepping(&Cycles {}, 7, 1)
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use crate::VGraph;
+use num::traits::Zero;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// A cost made of several objectives that are traded off against each other, such as travel
+/// time and fare.
+///
+/// Implemented for pairs and for arrays of any length.
+pub trait Objectives: Ord + Copy {
+    /// No cost in every objective.
+    fn zero() -> Self;
+
+    /// Adds each objective separately.
+    fn add(self, other: Self) -> Self;
+
+    /// True if `self` is no worse than `other` in every objective. Equal costs dominate each
+    /// other.
+    fn dominates(&self, other: &Self) -> bool;
+}
+
+impl<A, B> Objectives for (A, B)
+where
+    A: Zero + Ord + Copy,
+    B: Zero + Ord + Copy,
+{
+    fn zero() -> Self {
+        (A::zero(), B::zero())
+    }
+
+    fn add(self, other: Self) -> Self {
+        (self.0 + other.0, self.1 + other.1)
+    }
+
+    fn dominates(&self, other: &Self) -> bool {
+        self.0 <= other.0 && self.1 <= other.1
+    }
+}
+
+impl<D, const N: usize> Objectives for [D; N]
+where
+    D: Zero + Ord + Copy,
+{
+    fn zero() -> Self {
+        [D::zero(); N]
+    }
+
+    fn add(mut self, other: Self) -> Self {
+        for (a, b) in self.iter_mut().zip(other) {
+            *a = *a + b;
+        }
+        self
+    }
+
+    fn dominates(&self, other: &Self) -> bool {
+        self.iter().zip(other).all(|(a, b)| a <= b)
+    }
+}
+
+/// A graph whose edges cost something in several objectives at once.
+///
+/// Kept separate from `VGraph` so single objective graphs do not need to name a type for it.
+pub trait MultiCriteriaVGraph: VGraph {
+    type MultiDist: Objectives;
+
+    /// Cost of the edge `from -> to` in every objective. Each must be zero or more.
+    fn multi_dist(&self, from: Self::Node, to: Self::Node) -> Self::MultiDist;
+}
+
+impl<G> MultiCriteriaVGraph for &G
+where
+    G: MultiCriteriaVGraph + ?Sized,
+{
+    type MultiDist = G::MultiDist;
+
+    fn multi_dist(&self, from: Self::Node, to: Self::Node) -> Self::MultiDist {
+        (**self).multi_dist(from, to)
+    }
+}
+
+/// A path ending at `node`, as the label it extends.
+struct Label<N, M> {
+    node: N,
+    cost: M,
+    prev: Option<usize>,
+}
+
+/// Every path from `start` to `end` that no other path beats in all objectives at once, with
+/// its cost.
+///
+/// When no single path is best, such as the fastest route also being the most expensive, the
+/// caller gets every reasonable trade off to choose from. Paths are built by label setting:
+/// partial paths are extended in lexicographic order of their cost, and one is dropped as soon
+/// as a path already settled at the same node, or at `end`, dominates it. Only one of several
+/// paths with equal cost is kept. The result is sorted by cost, so the best in the first
+/// objective comes first.
+///
+/// The frontier can grow with the size of the graph, so this is much slower than a single
+/// objective search.
+pub fn pareto_paths<G>(g: G, start: G::Node, end: G::Node) -> Vec<(Vec<G::Node>, G::MultiDist)>
+where
+    G: MultiCriteriaVGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    let mut labels = vec![Label {
+        node: start,
+        cost: G::MultiDist::zero(),
+        prev: None,
+    }];
+    let mut to_explore = BinaryHeap::from([Reverse((G::MultiDist::zero(), 0))]);
+    // Costs of the paths settled at each node.
+    let mut settled: HashMap<G::Node, Vec<G::MultiDist>> = HashMap::new();
+    let dominated = |settled: &HashMap<G::Node, Vec<G::MultiDist>>, node, cost| {
+        [node, end].iter().any(|n| {
+            settled
+                .get(n)
+                .is_some_and(|costs| costs.iter().any(|c| c.dominates(&cost)))
+        })
+    };
+    let mut found = Vec::new();
+    while let Some(Reverse((cost, label))) = to_explore.pop() {
+        let node = labels[label].node;
+        // Anything that could dominate this path costs no more in the first objective, so it
+        // has been settled already.
+        if dominated(&settled, node, cost) {
+            continue;
+        }
+        settled.entry(node).or_default().push(cost);
+        if node == end {
+            found.push(label);
+            continue;
+        }
+        for next in g.out_edges(node) {
+            let next_cost = cost.add(g.multi_dist(node, next));
+            if !dominated(&settled, next, next_cost) {
+                labels.push(Label {
+                    node: next,
+                    cost: next_cost,
+                    prev: Some(label),
+                });
+                to_explore.push(Reverse((next_cost, labels.len() - 1)));
+            }
+        }
+    }
+
+    found
+        .into_iter()
+        .map(|label| {
+            let mut path = Vec::new();
+            let mut cur = Some(label);
+            while let Some(l) = cur {
+                path.push(labels[l].node);
+                cur = labels[l].prev;
+            }
+            path.reverse();
+            (path, labels[label].cost)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::random_graph;
+    use crate::{a_star_search, breadth_first_search, path_length, AdjacencyListGraph};
+
+    /// Edges with a travel time and a fare.
+    struct Routes {
+        edges: HashMap<(u8, u8), (u32, u32)>,
+    }
+
+    impl VGraph for Routes {
+        type Node = u8;
+
+        type Dist = u32;
+
+        fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+            let mut next: Vec<u8> = self
+                .edges
+                .keys()
+                .filter(|&&(from, _)| from == node)
+                .map(|&(_, to)| to)
+                .collect();
+            next.sort();
+            next
+        }
+
+        fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+            self.edges[&(from, to)].0
+        }
+    }
+
+    impl MultiCriteriaVGraph for Routes {
+        type MultiDist = (u32, u32);
+
+        fn multi_dist(&self, from: Self::Node, to: Self::Node) -> Self::MultiDist {
+            self.edges[&(from, to)]
+        }
+    }
+
+    #[test]
+    fn trades_time_for_fare() {
+        let g = Routes {
+            edges: HashMap::from([
+                // Fast and expensive.
+                ((0, 1), (1, 5)),
+                ((1, 3), (1, 5)),
+                // Slow and cheap.
+                ((0, 2), (3, 1)),
+                ((2, 3), (3, 1)),
+                // In between.
+                ((0, 3), (4, 4)),
+                // Switching lines is worse than going direct.
+                ((1, 2), (1, 1)),
+                ((2, 1), (1, 1)),
+                // The same cost as going direct.
+                ((0, 4), (2, 2)),
+                ((4, 3), (2, 2)),
+            ]),
+        };
+        assert_eq!(
+            vec![
+                (vec![0, 1, 3], (2, 10)),
+                (vec![0, 3], (4, 4)),
+                (vec![0, 2, 3], (6, 2)),
+            ],
+            pareto_paths(&g, 0, 3)
+        );
+        assert_eq!(vec![(vec![2], (0, 0))], pareto_paths(&g, 2, 2));
+        assert!(pareto_paths(&g, 3, 0).is_empty());
+    }
+
+    /// Distance and number of hops.
+    struct Hops {
+        g: AdjacencyListGraph<usize, u32>,
+    }
+
+    impl VGraph for Hops {
+        type Node = usize;
+
+        type Dist = u32;
+
+        fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+            self.g.out_edges(node)
+        }
+
+        fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+            self.g.dist(from, to)
+        }
+    }
+
+    impl MultiCriteriaVGraph for Hops {
+        type MultiDist = [u32; 2];
+
+        fn multi_dist(&self, from: Self::Node, to: Self::Node) -> Self::MultiDist {
+            [self.g.dist(from, to), 1]
+        }
+    }
+
+    #[test]
+    fn ends_of_the_frontier_are_single_objective_optima() {
+        for seed in 0..5 {
+            let g = Hops {
+                g: random_graph(30, 3, seed),
+            };
+            let frontier = pareto_paths(&g, 0, 1);
+            let Some(shortest) = a_star_search(&g, 0, |n| n == 1, |_| 0) else {
+                assert!(frontier.is_empty());
+                continue;
+            };
+            let fewest_hops = breadth_first_search(&g, 0, 1).unwrap();
+
+            let (first, last) = (&frontier[0], &frontier[frontier.len() - 1]);
+            assert_eq!(path_length(&g, shortest), first.1[0], "seed {seed}");
+            assert_eq!(fewest_hops.len() as u32 - 1, last.1[1], "seed {seed}");
+            for pair in frontier.windows(2) {
+                assert!(pair[0].1[0] < pair[1].1[0] && pair[0].1[1] > pair[1].1[1]);
+            }
+            for (path, cost) in &frontier {
+                assert_eq!(path_length(&g, path.clone()), cost[0]);
+                assert_eq!(path.len() as u32 - 1, cost[1]);
+            }
+        }
+    }
+}
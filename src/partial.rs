@@ -0,0 +1,146 @@
+use crate::{a_star_search, VGraph};
+use num::traits::{Float, Zero};
+use std::cmp::Ordering;
+use std::hash::Hash;
+use std::ops::Add;
+
+/// `a_star_search` for floating point distances, which are only `PartialOrd`.
+///
+/// NaN is treated as larger than every other value, like an infinite cost. An edge with a NaN
+/// distance makes the cost of any path through it NaN as well, so such a path is only returned
+/// when no path with a real cost exists. A NaN heuristic likewise puts its node behind every
+/// other node on the frontier.
+pub fn a_star_partial<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Float,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    a_star_search(Totally { g }, start, is_end, |n| TotalOrder(heuristic(n)))
+}
+
+/// A graph whose distances are wrapped in `TotalOrder`.
+struct Totally<G> {
+    g: G,
+}
+
+impl<G> VGraph for Totally<G>
+where
+    G: VGraph,
+{
+    type Node = G::Node;
+
+    type Dist = TotalOrder<G::Dist>;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.g.out_edges(node)
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        TotalOrder(self.g.dist(from, to))
+    }
+
+    fn is_terminal(&self, node: Self::Node) -> bool {
+        self.g.is_terminal(node)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.g.size_hint()
+    }
+}
+
+/// Orders a float totally, putting NaN after every other value.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TotalOrder<D>(pub(crate) D);
+
+impl<D: Float> Ord for TotalOrder<D> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.0.is_nan(), other.0.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => self
+                .0
+                .partial_cmp(&other.0)
+                .expect("Floats other than NaN should always compare."),
+        }
+    }
+}
+
+impl<D: Float> PartialOrd for TotalOrder<D> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<D: Float> PartialEq for TotalOrder<D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<D: Float> Eq for TotalOrder<D> {}
+
+impl<D: Add<Output = D>> Add for TotalOrder<D> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        TotalOrder(self.0 + other.0)
+    }
+}
+
+impl<D: Zero> Zero for TotalOrder<D> {
+    fn zero() -> Self {
+        TotalOrder(D::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AdjacencyListGraph;
+
+    #[test]
+    fn float_distances() {
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(0, 1, 0.5);
+        g.add_edge(1, 3, 0.25);
+        g.add_edge(0, 2, 0.1);
+        g.add_edge(2, 3, 1.5);
+        assert_eq!(
+            Some(vec![0, 1, 3]),
+            a_star_partial(&g, 0, |n| n == 3, |_| 0.0)
+        );
+    }
+
+    #[test]
+    fn nan_edge_is_a_last_resort() {
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(0, 1, f64::NAN);
+        g.add_edge(1, 3, 0.1);
+        g.add_edge(0, 2, 10.0);
+        g.add_edge(2, 3, 10.0);
+        assert_eq!(
+            Some(vec![0, 2, 3]),
+            a_star_partial(&g, 0, |n| n == 3, |_| 0.0)
+        );
+
+        let mut only_nan = AdjacencyListGraph::new();
+        only_nan.add_edge(0, 1, f64::NAN);
+        assert_eq!(
+            Some(vec![0, 1]),
+            a_star_partial(&only_nan, 0, |n| n == 1, |_| 0.0)
+        );
+    }
+}
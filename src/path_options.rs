@@ -0,0 +1,129 @@
+use crate::{path_length, VGraph};
+use num::traits::Zero;
+use std::ops::AddAssign;
+
+/// How to hand back a path found by a search, which always runs from start to end.
+///
+/// All options are off by default, giving the path unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PathOptions {
+    /// List the path from end to start.
+    pub reversed: bool,
+    /// Leave out the node the search started from.
+    pub skip_start: bool,
+    /// Leave out the node the search ended at.
+    pub skip_end: bool,
+    /// Also compute the cost of the path.
+    pub include_cost: bool,
+}
+
+/// A path shaped by `PathOptions::apply`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathOutput<N, D> {
+    pub path: Vec<N>,
+    /// The cost of the whole path, including any skipped edges. Only set with `include_cost`.
+    pub cost: Option<D>,
+}
+
+impl PathOptions {
+    /// Applies the options to `path`, a start to end path of `g`.
+    ///
+    /// Skipping refers to the start and end of the search, whether or not the path is reversed.
+    /// A path of a single node is both, so skipping either leaves it empty.
+    pub fn apply<G>(&self, g: G, mut path: Vec<G::Node>) -> PathOutput<G::Node, G::Dist>
+    where
+        G: VGraph,
+        G::Node: Copy,
+        G::Dist: Copy + AddAssign + Zero,
+    {
+        let cost = self.include_cost.then(|| path_length(g, path.clone()));
+
+        if self.skip_end {
+            path.pop();
+        }
+        if self.skip_start && !path.is_empty() {
+            path.remove(0);
+        }
+        if self.reversed {
+            path.reverse();
+        }
+        PathOutput { path, cost }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::Cycles;
+
+    #[test]
+    fn every_combination() {
+        let path = vec![1, 3, 4, 10];
+        for bits in 0..16 {
+            let options = PathOptions {
+                reversed: bits & 1 != 0,
+                skip_start: bits & 2 != 0,
+                skip_end: bits & 4 != 0,
+                include_cost: bits & 8 != 0,
+            };
+            let mut expected = path.clone();
+            if options.skip_start {
+                expected.retain(|&n| n != 1);
+            }
+            if options.skip_end {
+                expected.retain(|&n| n != 10);
+            }
+            if options.reversed {
+                expected.reverse();
+            }
+            let expected = PathOutput {
+                path: expected,
+                cost: options.include_cost.then_some(5),
+            };
+            assert_eq!(
+                expected,
+                options.apply(Cycles {}, path.clone()),
+                "{options:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn defaults_and_short_paths() {
+        assert_eq!(
+            PathOutput {
+                path: vec![1, 3],
+                cost: None
+            },
+            PathOptions::default().apply(Cycles {}, vec![1, 3])
+        );
+        let reversed = PathOptions {
+            reversed: true,
+            skip_start: true,
+            include_cost: true,
+            ..PathOptions::default()
+        };
+        assert_eq!(
+            PathOutput {
+                path: vec![3],
+                cost: Some(1)
+            },
+            reversed.apply(Cycles {}, vec![1, 3])
+        );
+
+        let skip_both = PathOptions {
+            skip_start: true,
+            skip_end: true,
+            include_cost: true,
+            ..PathOptions::default()
+        };
+        assert_eq!(
+            PathOutput {
+                path: vec![],
+                cost: Some(0)
+            },
+            skip_both.apply(Cycles {}, vec![4])
+        );
+        assert!(skip_both.apply(Cycles {}, vec![]).path.is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{distances_to, ReversibleVGraph};
+use num::traits::Zero;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Exact distances to the goal in a simplified version of a puzzle, used as a heuristic for the
+/// full puzzle.
+///
+/// `project` maps each state of the full puzzle to a state of the simplified one, typically by
+/// forgetting which tile is which for all but a few tiles. If every move of the full puzzle
+/// projects to a move of the simplified one that costs no more, the simplified distance never
+/// overestimates, so the heuristic is admissible. This is the classic heuristic for sliding
+/// puzzles, and several databases over different tiles can be combined with `max_heuristic`.
+pub struct PatternDatabase<A, D, P> {
+    project: P,
+    distances: HashMap<A, D>,
+}
+
+impl<A, D, P> PatternDatabase<A, D, P>
+where
+    A: Hash + Eq + Copy,
+    D: Zero + Ord + Copy,
+{
+    /// Precomputes the distance from every simplified state to `goal`, with a Dijkstra search
+    /// backward over `abstracted`.
+    ///
+    /// Every simplified state that can reach `goal` is visited, so the simplified puzzle must
+    /// be small enough to enumerate.
+    pub fn build<G>(abstracted: G, goal: A, project: P) -> Self
+    where
+        G: ReversibleVGraph<Node = A, Dist = D>,
+    {
+        PatternDatabase {
+            project,
+            distances: distances_to(abstracted, goal),
+        }
+    }
+
+    /// The distance from the projection of `node` to the simplified goal, or `None` if it can
+    /// not reach it, in which case neither can `node`.
+    pub fn distance<N>(&self, node: N) -> Option<D>
+    where
+        P: Fn(N) -> A,
+    {
+        self.distances.get(&(self.project)(node)).copied()
+    }
+
+    /// `distance` as a heuristic, estimating zero for nodes that can not reach the goal.
+    pub fn heuristic<N>(&self, node: N) -> D
+    where
+        P: Fn(N) -> A,
+    {
+        self.distance(node).unwrap_or_else(D::zero)
+    }
+
+    /// Number of simplified states stored.
+    pub fn len(&self) -> usize {
+        self.distances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.distances.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{a_star_search, dijkstra_iter, ExpansionRecorder, GraphCall, VGraph};
+
+    /// A 3 by 2 sliding puzzle, tiles listed row by row with 0 for the gap.
+    struct Sliding;
+
+    type Board = [u8; 6];
+
+    /// Stands for any of the tiles a simplified board does not track.
+    const ANY: u8 = u8::MAX;
+
+    const SOLVED: Board = [1, 2, 3, 4, 5, 0];
+
+    impl VGraph for Sliding {
+        type Node = Board;
+
+        type Dist = u32;
+
+        fn out_edges(&self, board: Self::Node) -> Vec<Self::Node> {
+            let gap = board.iter().position(|&t| t == 0).unwrap();
+            let (x, y) = (gap % 3, gap / 3);
+            let mut next = Vec::new();
+            let mut slide = |tile: usize| {
+                let mut moved = board;
+                moved.swap(gap, tile);
+                next.push(moved);
+            };
+            if x > 0 {
+                slide(gap - 1);
+            }
+            if x < 2 {
+                slide(gap + 1);
+            }
+            if y > 0 {
+                slide(gap - 3);
+            }
+            if y < 1 {
+                slide(gap + 3);
+            }
+            next
+        }
+
+        fn dist(&self, _from: Self::Node, _to: Self::Node) -> Self::Dist {
+            1
+        }
+    }
+
+    impl ReversibleVGraph for Sliding {
+        fn in_edges(&self, board: Self::Node) -> Vec<Self::Node> {
+            self.out_edges(board)
+        }
+    }
+
+    /// Keeps tiles 1, 2 and 3.
+    fn top_row(board: Board) -> Board {
+        board.map(|t| if t > 3 { ANY } else { t })
+    }
+
+    fn expansions(g: ExpansionRecorder<Sliding>) -> usize {
+        g.into_log()
+            .iter()
+            .filter(|call| matches!(call, GraphCall::OutEdges { .. }))
+            .count()
+    }
+
+    #[test]
+    fn admissible_and_faster() {
+        let db = PatternDatabase::build(Sliding, top_row(SOLVED), top_row);
+        // Where 1, 2, 3 and the gap can be, 6 * 5 * 4 * 3.
+        assert_eq!(360, db.len());
+
+        // Only half of the 720 boards can be solved.
+        let to_solved: HashMap<Board, u32> = dijkstra_iter(Sliding, SOLVED).collect();
+        assert_eq!(360, to_solved.len());
+        for (&board, &dist) in &to_solved {
+            assert!(db.heuristic(board) <= dist, "{board:?}");
+        }
+        assert_eq!(Some(0), db.distance(SOLVED));
+
+        let start = [0, 5, 4, 3, 2, 1];
+        let plain = ExpansionRecorder::new(Sliding);
+        let shortest = a_star_search(&plain, start, |b| b == SOLVED, |_| 0).unwrap();
+        let guided = ExpansionRecorder::new(Sliding);
+        let path = a_star_search(&guided, start, |b| b == SOLVED, |b| db.heuristic(b)).unwrap();
+        assert_eq!(shortest.len(), path.len());
+        assert_eq!(to_solved[&start] as usize, path.len() - 1);
+        assert!(expansions(guided) < expansions(plain));
+    }
+}
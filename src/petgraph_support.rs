@@ -0,0 +1,147 @@
+//! `VGraph` implementations for petgraph's graphs, so they can be searched directly.
+//!
+//! Nodes are petgraph's `NodeIndex` and distances are the edge weights. Implemented on the
+//! graphs themselves, search a borrowed graph with `a_star_search(&graph, ...)`.
+use crate::{ReversibleVGraph, UndirectedVGraph, VGraph};
+use petgraph::graph::{Graph, IndexType, NodeIndex};
+use petgraph::stable_graph::StableGraph;
+use petgraph::{Direction, EdgeType, Undirected};
+
+impl<N, E, Ty, Ix> VGraph for Graph<N, E, Ty, Ix>
+where
+    E: Copy,
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    type Node = NodeIndex<Ix>;
+
+    type Dist = E;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.neighbors(node).collect()
+    }
+
+    /// The weight of an edge between the nodes. When there are parallel edges, any one of them
+    /// may be used.
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        let edge = self
+            .find_edge(from, to)
+            .expect("dist is only defined for nodes connected by an edge.");
+        self[edge]
+    }
+}
+
+impl<N, E, Ty, Ix> ReversibleVGraph for Graph<N, E, Ty, Ix>
+where
+    E: Copy,
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    fn in_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.neighbors_directed(node, Direction::Incoming).collect()
+    }
+}
+
+impl<N, E, Ix> UndirectedVGraph for Graph<N, E, Undirected, Ix>
+where
+    E: Copy,
+    Ix: IndexType,
+{
+}
+
+impl<N, E, Ty, Ix> VGraph for StableGraph<N, E, Ty, Ix>
+where
+    E: Copy,
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    type Node = NodeIndex<Ix>;
+
+    type Dist = E;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.neighbors(node).collect()
+    }
+
+    /// The weight of an edge between the nodes. When there are parallel edges, any one of them
+    /// may be used.
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        let edge = self
+            .find_edge(from, to)
+            .expect("dist is only defined for nodes connected by an edge.");
+        self[edge]
+    }
+}
+
+impl<N, E, Ty, Ix> ReversibleVGraph for StableGraph<N, E, Ty, Ix>
+where
+    E: Copy,
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    fn in_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.neighbors_directed(node, Direction::Incoming).collect()
+    }
+}
+
+impl<N, E, Ix> UndirectedVGraph for StableGraph<N, E, Undirected, Ix>
+where
+    E: Copy,
+    Ix: IndexType,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{a_star_search, minimum_spanning_tree, path_length};
+    use petgraph::graph::UnGraph;
+
+    #[test]
+    fn a_star_search_on_petgraph() {
+        let mut g = Graph::<&str, u32>::new();
+        let a = g.add_node("a");
+        let b = g.add_node("b");
+        let c = g.add_node("c");
+        let d = g.add_node("d");
+        g.add_edge(a, b, 1);
+        g.add_edge(b, d, 5);
+        g.add_edge(a, c, 2);
+        g.add_edge(c, d, 2);
+
+        let path = a_star_search(&g, a, |n| n == d, |_| 0).unwrap();
+        assert_eq!(vec![a, c, d], path);
+        assert_eq!(4, path_length(&g, path));
+        assert_eq!(None, a_star_search(&g, d, |n| n == a, |_| 0));
+        assert_eq!(vec![b, c], {
+            let mut incoming = g.in_edges(d);
+            incoming.sort();
+            incoming
+        });
+    }
+
+    #[test]
+    fn a_star_search_on_stable_graph_with_removed_node() {
+        let mut g = StableGraph::<(), u32>::new();
+        let a = g.add_node(());
+        let b = g.add_node(());
+        let c = g.add_node(());
+        g.add_edge(a, b, 1);
+        g.add_edge(b, c, 1);
+        g.add_edge(a, c, 5);
+        assert_eq!(Some(vec![a, b, c]), a_star_search(&g, a, |n| n == c, |_| 0));
+        g.remove_node(b);
+        assert_eq!(Some(vec![a, c]), a_star_search(&g, a, |n| n == c, |_| 0));
+    }
+
+    #[test]
+    fn minimum_spanning_tree_on_undirected_petgraph() {
+        let mut g = UnGraph::<(), u32>::new_undirected();
+        let nodes: Vec<_> = (0..3).map(|_| g.add_node(())).collect();
+        g.add_edge(nodes[0], nodes[1], 1);
+        g.add_edge(nodes[1], nodes[2], 1);
+        g.add_edge(nodes[0], nodes[2], 5);
+        let tree = minimum_spanning_tree(&g, &nodes);
+        assert_eq!(2, tree.iter().map(|&(_, _, d)| d).sum::<u32>());
+    }
+}
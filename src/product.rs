@@ -0,0 +1,83 @@
+use crate::VGraph;
+use num::traits::Zero;
+use std::ops::Add;
+
+/// The joint state space of two graphs, for example two agents moving on their own maps.
+///
+/// A node is a pair of nodes, one from each graph, and every edge advances exactly one of the
+/// two components. The distance of an edge is the sum of the component distances, a component
+/// that stays put contributes zero.
+///
+/// The number of nodes is the product of the sizes of both graphs, so searches over a product
+/// graph blow up quickly. Chaining products to model more agents multiplies again.
+pub struct ProductGraph<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> ProductGraph<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        ProductGraph { a, b }
+    }
+}
+
+impl<A, B> VGraph for ProductGraph<A, B>
+where
+    A: VGraph,
+    B: VGraph<Dist = A::Dist>,
+    A::Node: Eq + Copy,
+    B::Node: Eq + Copy,
+    A::Dist: Zero + Add<Output = A::Dist>,
+{
+    type Node = (A::Node, B::Node);
+
+    type Dist = A::Dist;
+
+    fn out_edges(&self, (a, b): Self::Node) -> Vec<Self::Node> {
+        let a_moves = self.a.out_edges(a).into_iter().map(|next| (next, b));
+        let b_moves = self.b.out_edges(b).into_iter().map(|next| (a, next));
+        a_moves.chain(b_moves).collect()
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        let mut distance = A::Dist::zero();
+        if from.0 != to.0 {
+            distance = distance + self.a.dist(from.0, to.0);
+        }
+        if from.1 != to.1 {
+            distance = distance + self.b.dist(from.1, to.1);
+        }
+        distance
+    }
+
+    fn is_terminal(&self, (a, b): Self::Node) -> bool {
+        self.a.is_terminal(a) && self.b.is_terminal(b)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.a.size_hint()?.checked_mul(self.b.size_hint()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::Ex;
+    use crate::{a_star_search, path_length};
+
+    #[test]
+    fn out_edges_advance_one_component() {
+        let product = ProductGraph::new(Ex::new(), Ex::new());
+        assert_eq!(vec![(2, 1), (1, 2)], product.out_edges((1, 1)));
+        assert_eq!(vec![(3, 3)], product.out_edges((2, 3)));
+    }
+
+    #[test]
+    fn a_star_search_in_product() {
+        let product = ProductGraph::new(Ex::new(), Ex::new());
+        let path = a_star_search(&product, (1, 1), |n| n == (3, 3), |_| 0).unwrap();
+        assert_eq!(Some(&(1, 1)), path.first());
+        assert_eq!(Some(&(3, 3)), path.last());
+        assert_eq!(4, path_length(&product, path));
+    }
+}
@@ -0,0 +1,62 @@
+//! Property tests checking the searches agree with each other on random graphs.
+use crate::{
+    a_star_search, breadth_first_search, distance, distances_to, path_length, verify_shortest_path,
+    AdjacencyListGraph,
+};
+use proptest::prelude::*;
+
+/// Up to `max_nodes` nodes with random directed edges between them, not necessarily connected.
+fn arbitrary_graph(
+    max_nodes: usize,
+    max_dist: u32,
+) -> impl Strategy<Value = AdjacencyListGraph<usize, u32>> {
+    (1..=max_nodes).prop_flat_map(move |n| {
+        prop::collection::vec((0..n, 0..n, 1..=max_dist), 0..n * 3).prop_map(move |edges| {
+            let mut g = AdjacencyListGraph::new();
+            for node in 0..n {
+                g.add_node(node);
+            }
+            for (from, to, dist) in edges {
+                if from != to && !g.edges_from(from).iter().any(|&(next, _)| next == to) {
+                    g.add_edge(from, to, dist);
+                }
+            }
+            g
+        })
+    })
+}
+
+/// A graph along with a start and end node in it.
+fn graph_and_query(
+    max_nodes: usize,
+    max_dist: u32,
+) -> impl Strategy<Value = (AdjacencyListGraph<usize, u32>, usize, usize)> {
+    arbitrary_graph(max_nodes, max_dist).prop_flat_map(|g| {
+        let n = g.num_nodes();
+        (Just(g), 0..n, 0..n)
+    })
+}
+
+proptest! {
+    #[test]
+    fn bfs_hops_match_dijkstra_on_unit_weights((g, start, end) in graph_and_query(20, 1)) {
+        let hops = breadth_first_search(&g, start, end).map(|path| path.len() as u32 - 1);
+        prop_assert_eq!(distance(&g, start, end), hops);
+    }
+
+    #[test]
+    fn a_star_matches_dijkstra(
+        (g, start, end) in graph_and_query(20, 50),
+        percent in 0u32..=100,
+    ) {
+        // Scaling the exact distances down keeps the heuristic admissible.
+        let exact = distances_to(&g, end);
+        let heuristic = |n| exact.get(&n).map_or(0, |d| d * percent / 100);
+        let path = a_star_search(&g, start, |n| n == end, heuristic);
+        let dijkstra = distance(&g, start, end);
+        prop_assert_eq!(dijkstra, path.clone().map(|path| path_length(&g, path)));
+        if let (Some(path), Some(cost)) = (path, dijkstra) {
+            prop_assert_eq!(Ok(()), verify_shortest_path(&g, &path, cost));
+        }
+    }
+}
@@ -0,0 +1,336 @@
+use crate::{explore, explore_with, ClosedSet, VGraph};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::ControlFlow;
+
+/// Every node reachable from `start`, including `start` itself.
+///
+/// This is the visited set of a breadth first search, and only returns if finitely many nodes
+/// can be reached.
+pub fn reachable_set<G>(g: G, start: G::Node) -> HashSet<G::Node>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    let mut reached = HashSet::new();
+    explore(g, start, &mut reached, |reached, cur, _| {
+        reached.insert(cur);
+        ControlFlow::<()>::Continue(())
+    });
+    reached
+}
+
+/// Maps each of `nodes` to every node reachable from it by a path of at least one edge.
+///
+/// A node only reaches itself if it is on a cycle. Runs a breadth first search from each node,
+/// so it is meant for graphs small enough to enumerate.
+pub fn transitive_closure<G>(g: G, nodes: &[G::Node]) -> HashMap<G::Node, HashSet<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    nodes
+        .iter()
+        .map(|&node| (node, reachable_from(&g, node)))
+        .collect()
+}
+
+/// `transitive_closure` for at most 64 nodes, packed into one bitset row per node.
+///
+/// Bit `j` of row `i` is set when `nodes[i]` reaches `nodes[j]` by a path of at least one edge.
+/// Only paths through `nodes` count, edges leading elsewhere are ignored. Rows are combined a
+/// whole word at a time, so this is much faster than the `HashSet` version on small dense
+/// graphs.
+///
+/// Panics if there are more than 64 nodes.
+pub fn reachability_bitset<G>(g: G, nodes: &[G::Node]) -> Vec<u64>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    assert!(
+        nodes.len() <= 64,
+        "reachability_bitset supports at most 64 nodes, got {}",
+        nodes.len()
+    );
+    let index: HashMap<G::Node, usize> = nodes.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+    let mut rows: Vec<u64> = nodes
+        .iter()
+        .map(|&node| {
+            g.out_edges(node)
+                .into_iter()
+                .filter_map(|next| index.get(&next))
+                .fold(0, |row, &j| row | (1 << j))
+        })
+        .collect();
+
+    // Warshall's algorithm, after step `k` the rows include paths through the first `k` nodes.
+    for k in 0..rows.len() {
+        let through_k = rows[k];
+        for row in rows.iter_mut() {
+            if *row & (1 << k) != 0 {
+                *row |= through_k;
+            }
+        }
+    }
+    rows
+}
+
+/// Nodes whose fewest hops from `start` is exactly `k`, ignoring edge distances.
+///
+/// Stops the breadth first search at the first node more than `k` hops away, so nodes further
+/// away are never visited. `k == 0` gives just `start`.
+pub fn nodes_at_hop<G>(g: G, start: G::Node, k: usize) -> Vec<G::Node>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    let mut hops: HashMap<G::Node, usize> = HashMap::new();
+    let mut ring = Vec::new();
+    explore(g, start, &mut hops, |hops, cur, from| {
+        let hop = from.map_or(0, |from| hops[&from] + 1);
+        if hop > k {
+            return ControlFlow::Break(());
+        }
+        hops.insert(cur, hop);
+        if hop == k {
+            ring.push(cur);
+        }
+        ControlFlow::Continue(())
+    });
+    ring
+}
+
+/// A breadth first search driven one node at a time, which can take on more start nodes as it
+/// goes.
+///
+/// Each call to `next` visits one node and returns it. Nodes are visited at most once, in the
+/// order they were found. Found nodes are kept in a `HashSet` unless another `ClosedSet` is
+/// given with `with_closed`.
+pub struct Bfs<G, C = HashSet<<G as VGraph>::Node>>
+where
+    G: VGraph,
+{
+    g: G,
+    to_visit: VecDeque<G::Node>,
+    seen: C,
+}
+
+impl<G> Bfs<G>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    pub fn new(g: G, start: G::Node) -> Self {
+        Bfs::with_closed(g, start, HashSet::new())
+    }
+}
+
+impl<G, C> Bfs<G, C>
+where
+    G: VGraph,
+    G::Node: Copy,
+    C: ClosedSet<G::Node>,
+{
+    /// A search that records the nodes it has found in `closed`, which should start out empty.
+    pub fn with_closed(g: G, start: G::Node, mut closed: C) -> Self {
+        closed.insert(start);
+        Bfs {
+            g,
+            to_visit: VecDeque::from([start]),
+            seen: closed,
+        }
+    }
+
+    /// Adds another node to search from.
+    ///
+    /// It is visited after the nodes already waiting, so nodes are no longer in order of hops
+    /// from the nearest start. Does nothing if the node was already found.
+    pub fn add_source(&mut self, node: G::Node) {
+        if self.seen.insert(node) {
+            self.to_visit.push_back(node);
+        }
+    }
+
+    /// True if `node` has been found, whether or not it was visited yet.
+    pub fn seen(&self, node: G::Node) -> bool {
+        self.seen.contains(&node)
+    }
+}
+
+impl<G, C> Iterator for Bfs<G, C>
+where
+    G: VGraph,
+    G::Node: Copy,
+    C: ClosedSet<G::Node>,
+{
+    type Item = G::Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cur = self.to_visit.pop_front()?;
+        for next in self.g.out_edges(cur) {
+            if self.seen.insert(next) {
+                self.to_visit.push_back(next);
+            }
+        }
+        Some(cur)
+    }
+}
+
+fn reachable_from<G>(g: G, start: G::Node) -> HashSet<G::Node>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+{
+    let mut closed = ReturnsToStart {
+        seen: HashSet::new(),
+        start,
+        returned: false,
+    };
+    explore_with(g, start, &mut (), &mut closed, |_, _, _| {
+        ControlFlow::<()>::Continue(())
+    });
+    let mut reached = closed.seen;
+    // The start is only reached by a path of at least one edge if it is on a cycle.
+    if !closed.returned {
+        reached.remove(&start);
+    }
+    reached
+}
+
+/// A closed set that notes whether a traversal found an edge back into its start.
+struct ReturnsToStart<N> {
+    seen: HashSet<N>,
+    start: N,
+    returned: bool,
+}
+
+impl<N> ClosedSet<N> for ReturnsToStart<N>
+where
+    N: Hash + Eq,
+{
+    fn insert(&mut self, node: N) -> bool {
+        // The first insert is the start itself, any later one comes from an edge.
+        if node == self.start && !self.seen.is_empty() {
+            self.returned = true;
+        }
+        self.seen.insert(node)
+    }
+
+    fn contains(&self, node: &N) -> bool {
+        self.seen.contains(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::{Cycles, Ex};
+    use crate::{AdjacencyListGraph, BitSetClosed, ExpansionRecorder};
+
+    #[test]
+    fn chain_reaches_through_cycle() {
+        let nodes: Vec<usize> = (1..=10).collect();
+        let closure = transitive_closure(Cycles {}, &nodes);
+        assert!(closure[&7].contains(&5));
+        assert!(!closure[&7].contains(&7));
+        assert!(closure[&1].contains(&1));
+        assert_eq!(HashSet::from([1, 2, 3, 4, 5, 6, 10]), closure[&1]);
+    }
+
+    #[test]
+    fn closure_expands_each_node_once() {
+        let g = ExpansionRecorder::new(Cycles {});
+        let closure = transitive_closure(&g, &[7]);
+        assert_eq!(9, closure[&7].len());
+        assert_eq!(10, g.into_log().len());
+
+        let mut looped = AdjacencyListGraph::new();
+        looped.add_edge(1, 1, 1);
+        looped.add_edge(1, 2, 1);
+        let closure = transitive_closure(&looped, &[1, 2]);
+        assert_eq!(HashSet::from([1, 2]), closure[&1]);
+        assert!(closure[&2].is_empty());
+    }
+
+    #[test]
+    fn reachable_set_from_seven() {
+        assert_eq!(
+            HashSet::from([7, 8, 9, 10, 1, 2, 3, 4, 5, 6]),
+            reachable_set(Cycles {}, 7)
+        );
+        assert_eq!(HashSet::from([3]), reachable_set(Ex::new(), 3));
+    }
+
+    #[test]
+    fn closure_of_a_line() {
+        let closure = transitive_closure(Ex::new(), &[1, 2, 3]);
+        assert_eq!(HashSet::from([2, 3]), closure[&1]);
+        assert_eq!(HashSet::from([3]), closure[&2]);
+        assert!(closure[&3].is_empty());
+    }
+
+    #[test]
+    fn bitset_matches_closure() {
+        let nodes: Vec<usize> = (1..=10).collect();
+        let closure = transitive_closure(Cycles {}, &nodes);
+        let rows = reachability_bitset(Cycles {}, &nodes);
+        for (i, from) in nodes.iter().enumerate() {
+            for (j, to) in nodes.iter().enumerate() {
+                assert_eq!(
+                    closure[from].contains(to),
+                    rows[i] & (1 << j) != 0,
+                    "{from} to {to}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn nodes_two_hops_away() {
+        let mut ring = nodes_at_hop(Cycles {}, 1, 2);
+        ring.sort();
+        // 3 is one hop from 1 even though the edge is longer than 1 -> 2 -> 3.
+        assert_eq!(vec![4, 5, 6], ring);
+        assert_eq!(vec![1], nodes_at_hop(Cycles {}, 1, 0));
+        assert_eq!(vec![10], nodes_at_hop(Cycles {}, 7, 3));
+        assert!(nodes_at_hop(Ex::new(), 1, 3).is_empty());
+    }
+
+    #[test]
+    fn bfs_takes_a_second_source() {
+        let mut bfs = Bfs::new(Cycles {}, 3);
+        assert_eq!(Some(3), bfs.next());
+        assert_eq!(Some(4), bfs.next());
+        bfs.add_source(7);
+        // Already found from 4.
+        bfs.add_source(10);
+        assert!(bfs.seen(10));
+        assert!(!bfs.seen(8));
+        let rest: Vec<usize> = bfs.collect();
+        assert_eq!(vec![5, 10, 7, 1, 8, 2, 9, 6], rest);
+
+        let mut bfs = Bfs::new(Ex::new(), 3);
+        assert_eq!(Some(3), bfs.next());
+        assert_eq!(None, bfs.next());
+        bfs.add_source(1);
+        assert_eq!(vec![1, 2], bfs.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn bfs_with_bitset_visits_the_same_nodes() {
+        for start in 1..=10 {
+            assert_eq!(
+                Bfs::new(Cycles {}, start).collect::<Vec<_>>(),
+                Bfs::with_closed(Cycles {}, start, BitSetClosed::new()).collect::<Vec<_>>()
+            );
+        }
+
+        let mut bfs = Bfs::with_closed(Cycles {}, 3, BitSetClosed::new());
+        assert_eq!(Some(3), bfs.next());
+        assert!(bfs.seen(4));
+        assert!(!bfs.seen(7));
+        bfs.add_source(7);
+        assert!(bfs.seen(7));
+    }
+}
@@ -0,0 +1,112 @@
+use crate::VGraph;
+use std::cell::RefCell;
+
+/// A single query made against a graph, along with the value it returned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphCall<N, D> {
+    OutEdges { node: N, result: Vec<N> },
+    Dist { from: N, to: N, result: D },
+}
+
+/// Wraps a graph and logs every `out_edges` and `dist` call in the order they were made.
+///
+/// Searches take their graph by value, so pass `&recorder` to a search and read the log
+/// afterward. Useful for replaying the exact queries of a search when debugging
+/// nondeterminism.
+pub struct ExpansionRecorder<G>
+where
+    G: VGraph,
+{
+    g: G,
+    log: RefCell<Vec<GraphCall<G::Node, G::Dist>>>,
+}
+
+impl<G> ExpansionRecorder<G>
+where
+    G: VGraph,
+{
+    pub fn new(g: G) -> Self {
+        ExpansionRecorder {
+            g,
+            log: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// The calls recorded so far, oldest first.
+    pub fn log(&self) -> Vec<GraphCall<G::Node, G::Dist>>
+    where
+        G::Node: Clone,
+        G::Dist: Clone,
+    {
+        self.log.borrow().clone()
+    }
+
+    /// Consumes the recorder, returning the recorded calls.
+    pub fn into_log(self) -> Vec<GraphCall<G::Node, G::Dist>> {
+        self.log.into_inner()
+    }
+}
+
+impl<G> VGraph for ExpansionRecorder<G>
+where
+    G: VGraph,
+    G::Node: Clone,
+    G::Dist: Clone,
+{
+    type Node = G::Node;
+
+    type Dist = G::Dist;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        let result = self.g.out_edges(node.clone());
+        self.log.borrow_mut().push(GraphCall::OutEdges {
+            node,
+            result: result.clone(),
+        });
+        result
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        let result = self.g.dist(from.clone(), to.clone());
+        self.log.borrow_mut().push(GraphCall::Dist {
+            from,
+            to,
+            result: result.clone(),
+        });
+        result
+    }
+
+    fn is_terminal(&self, node: Self::Node) -> bool {
+        self.g.is_terminal(node)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.g.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::breadth_first_search;
+    use crate::test_graphs::Ex;
+
+    #[test]
+    fn records_bfs_queries() {
+        let recorder = ExpansionRecorder::new(Ex::new());
+        assert_eq!(Some(vec![1, 2, 3]), breadth_first_search(&recorder, 1, 3));
+        assert_eq!(
+            vec![
+                GraphCall::OutEdges {
+                    node: 1,
+                    result: vec![2]
+                },
+                GraphCall::OutEdges {
+                    node: 2,
+                    result: vec![3]
+                },
+            ],
+            recorder.log()
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use crate::VGraph;
+use num::traits::Zero;
+use priority_queue::PriorityQueue;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Each node mapped to its predecessors on shortest paths.
+type Preds<N> = HashMap<N, Vec<N>>;
+
+/// Every way a shortest path from `start` can arrive at each node.
+///
+/// Maps each node reachable from `start` to all of its predecessors on some shortest path, where
+/// the `prev` map of a single search only keeps one of them. `start` maps to an empty list.
+/// Following predecessors back from a node gives exactly the shortest paths to it, so the map is
+/// the basis for counting or listing all of them.
+///
+/// Edges must cost more than zero. A path of zero cost edges could lead back to where it started,
+/// and the predecessors would no longer form a DAG.
+pub fn shortest_path_dag<G>(g: G, start: G::Node) -> HashMap<G::Node, Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    shortest_path_dag_ordered(g, start).1
+}
+
+/// `shortest_path_dag`, along with the reachable nodes in the order they were settled, which
+/// lists every node after all of its predecessors.
+pub(crate) fn shortest_path_dag_ordered<G>(g: G, start: G::Node) -> (Vec<G::Node>, Preds<G::Node>)
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let mut to_explore = PriorityQueue::new();
+    to_explore.push(start, Reverse(G::Dist::zero()));
+    let mut dist_from_start: HashMap<G::Node, G::Dist> = HashMap::new();
+    dist_from_start.insert(start, G::Dist::zero());
+    let mut preds: HashMap<G::Node, Vec<G::Node>> = HashMap::new();
+    preds.insert(start, Vec::new());
+    let mut settled: HashSet<G::Node> = HashSet::new();
+    let mut order = Vec::new();
+
+    while let Some((cur, Reverse(cur_distance))) = to_explore.pop() {
+        settled.insert(cur);
+        order.push(cur);
+        for next in g.out_edges(cur) {
+            if settled.contains(&next) {
+                continue;
+            }
+            let start_to_next = cur_distance + g.dist(cur, next);
+            match dist_from_start.get(&next) {
+                Some(&best) if start_to_next > best => {}
+                Some(&best) if start_to_next == best => {
+                    let next_preds = preds.get_mut(&next).expect("Reached nodes have preds.");
+                    if !next_preds.contains(&cur) {
+                        next_preds.push(cur);
+                    }
+                }
+                _ => {
+                    dist_from_start.insert(next, start_to_next);
+                    preds.insert(next, vec![cur]);
+                    to_explore.push(next, Reverse(start_to_next));
+                }
+            }
+        }
+    }
+
+    (order, preds)
+}
+
+/// Number of distinct minimum cost paths from `start` to `end`, 0 if `end` cannot be reached.
+///
+/// Counts are summed back along `shortest_path_dag`, so the same restriction to edges costing
+/// more than zero applies. Graphs with more paths than fit in a `u64` give `u64::MAX`.
+pub fn count_shortest_paths<G>(g: G, start: G::Node, end: G::Node) -> u64
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let preds = shortest_path_dag(g, start);
+    if !preds.contains_key(&end) {
+        return 0;
+    }
+
+    // Each node is counted once all of its predecessors are. The DAG can be as deep as the graph
+    // is large, so this keeps its own stack instead of recursing.
+    let mut counts: HashMap<G::Node, u64> = HashMap::new();
+    counts.insert(start, 1);
+    let mut stack = vec![end];
+    while let Some(&cur) = stack.last() {
+        if counts.contains_key(&cur) {
+            stack.pop();
+            continue;
+        }
+        let cur_preds = &preds[&cur];
+        let uncounted: Vec<G::Node> = cur_preds
+            .iter()
+            .copied()
+            .filter(|pred| !counts.contains_key(pred))
+            .collect();
+        if uncounted.is_empty() {
+            let count = cur_preds
+                .iter()
+                .fold(0u64, |total, pred| total.saturating_add(counts[pred]));
+            counts.insert(cur, count);
+            stack.pop();
+        } else {
+            stack.extend(uncounted);
+        }
+    }
+
+    counts[&end]
+}
+
+/// The shortest path from `start` to `end` that comes first when comparing node sequences.
+///
+/// Among all paths of minimum cost, this walks forward through `shortest_path_dag` always
+/// stepping to the smallest node that still leads to `end` by a shortest path. Unlike
+/// `a_star_deterministic`, which only breaks ties on the frontier, the result does not depend on
+/// the order paths happen to be found in. Edges must cost more than zero.
+pub fn lex_smallest_shortest_path<G>(g: G, start: G::Node, end: G::Node) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let preds = shortest_path_dag(g, start);
+    if !preds.contains_key(&end) {
+        return None;
+    }
+
+    // Reverses the part of the DAG that leads to `end`, so it can be walked forward.
+    let mut succs: HashMap<G::Node, Vec<G::Node>> = HashMap::new();
+    let mut to_visit = vec![end];
+    let mut leads_to_end = HashSet::from([end]);
+    while let Some(cur) = to_visit.pop() {
+        for &pred in &preds[&cur] {
+            succs.entry(pred).or_default().push(cur);
+            if leads_to_end.insert(pred) {
+                to_visit.push(pred);
+            }
+        }
+    }
+
+    let mut path = vec![start];
+    let mut cur = start;
+    while cur != end {
+        cur = *succs[&cur]
+            .iter()
+            .min()
+            .expect("Nodes before the end have a successor toward it.");
+        path.push(cur);
+    }
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::Cycles;
+    use crate::{dijkstra_iter, path_length, FnGraph, GridGraph};
+
+    #[test]
+    fn ties_keep_every_predecessor() {
+        let mut dag = shortest_path_dag(Cycles {}, 4);
+        dag.values_mut().for_each(|preds| preds.sort());
+        assert_eq!(Vec::<usize>::new(), dag[&4]);
+        assert_eq!(vec![4], dag[&5]);
+        assert_eq!(vec![4], dag[&10]);
+        // 4 -> 5 -> 1 and 4 -> 10 -> 1 both cost 2.
+        assert_eq!(vec![5, 10], dag[&1]);
+        assert_eq!(vec![1], dag[&3]);
+        assert!(!dag.contains_key(&7));
+    }
+
+    #[test]
+    fn predecessors_are_on_shortest_paths() {
+        for start in 1..=10 {
+            let dist: HashMap<usize, usize> = dijkstra_iter(Cycles {}, start).collect();
+            let dag = shortest_path_dag(Cycles {}, start);
+            assert_eq!(
+                dist.keys().collect::<HashSet<_>>(),
+                dag.keys().collect::<HashSet<_>>()
+            );
+            for (node, preds) in dag {
+                for pred in preds {
+                    assert_eq!(
+                        dist[&node],
+                        dist[&pred] + path_length(Cycles {}, vec![pred, node]),
+                        "{pred} to {node} from {start}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn counts_paths_through_ties() {
+        assert_eq!(1, count_shortest_paths(Cycles {}, 4, 4));
+        assert_eq!(1, count_shortest_paths(Cycles {}, 4, 5));
+        assert_eq!(2, count_shortest_paths(Cycles {}, 4, 1));
+        assert_eq!(2, count_shortest_paths(Cycles {}, 4, 3));
+        assert_eq!(0, count_shortest_paths(Cycles {}, 4, 7));
+    }
+
+    #[test]
+    fn counts_grid_paths() {
+        // Every shortest path makes 3 moves right and 3 down, in any of 6 choose 3 orders.
+        assert_eq!(
+            20,
+            count_shortest_paths(&GridGraph::new(4, 4), (0, 0), (3, 3))
+        );
+
+        let mut grid = GridGraph::new(4, 4);
+        grid.block((1, 1));
+        // Of the 20, 2 * 6 pass through (1, 1).
+        assert_eq!(8, count_shortest_paths(&grid, (0, 0), (3, 3)));
+    }
+
+    #[test]
+    fn saturates_on_overflow() {
+        // A chain of diamonds, each one doubling the number of paths.
+        let chain = FnGraph::new(
+            |(level, _side): (u32, u32)| {
+                if level < 100 {
+                    vec![(level + 1, 0), (level + 1, 1)]
+                } else {
+                    vec![]
+                }
+            },
+            |_, _| 1u32,
+        );
+        assert_eq!(1 << 40, count_shortest_paths(&chain, (0, 0), (41, 0)));
+        assert_eq!(u64::MAX, count_shortest_paths(&chain, (0, 0), (100, 0)));
+    }
+
+    #[test]
+    fn lex_smallest_picks_smallest_tie() {
+        // 4 -> 5 -> 1 and 4 -> 10 -> 1 both cost 2.
+        assert_eq!(
+            Some(vec![4, 5, 1]),
+            lex_smallest_shortest_path(Cycles {}, 4, 1)
+        );
+        assert_eq!(
+            Some(vec![4, 5, 1, 3]),
+            lex_smallest_shortest_path(Cycles {}, 4, 3)
+        );
+        assert_eq!(Some(vec![4]), lex_smallest_shortest_path(Cycles {}, 4, 4));
+        assert_eq!(None, lex_smallest_shortest_path(Cycles {}, 4, 7));
+
+        assert_eq!(
+            Some(vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)]),
+            lex_smallest_shortest_path(&GridGraph::new(3, 3), (0, 0), (2, 2))
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use crate::mst::spanning_forest;
+use crate::{back_track, dijkstra_until, AdjacencyListGraph, UndirectedVGraph};
+use num::traits::Zero;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Approximates the cheapest tree connecting all of `terminals`.
+///
+/// Uses the shortest path metric closure: the terminals are joined by a complete graph whose
+/// distances are shortest path costs, that graph's minimum spanning tree is expanded back into
+/// the underlying paths, and the result is spanned and trimmed of dangling non-terminal nodes.
+/// The returned tree costs at most twice the optimal Steiner tree.
+///
+/// Terminals that can not be reached from each other end up in separate trees.
+pub fn steiner_tree_approx<G>(g: G, terminals: &[G::Node]) -> Vec<(G::Node, G::Node, G::Dist)>
+where
+    G: UndirectedVGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let trees: Vec<_> = terminals
+        .iter()
+        .map(|&terminal| dijkstra_until(&g, terminal, || false))
+        .collect();
+
+    let mut closure = AdjacencyListGraph::new();
+    for (i, &from) in terminals.iter().enumerate() {
+        closure.add_node(from);
+        for &to in &terminals[i + 1..] {
+            if let Some(&dist) = trees[i].dist.get(&to) {
+                closure.add_edge(from, to, dist);
+                closure.add_edge(to, from, dist);
+            }
+        }
+    }
+
+    // Expand every closure edge into the shortest path it stands for.
+    let index: HashMap<G::Node, usize> =
+        terminals.iter().enumerate().map(|(i, &t)| (t, i)).collect();
+    let mut expanded = AdjacencyListGraph::new();
+    let mut added = HashSet::new();
+    for (from, to, _) in spanning_forest(&closure, terminals) {
+        let path = back_track(&trees[index[&from]].prev, to);
+        for window in path.windows(2) {
+            let (a, b) = (window[0], window[1]);
+            if added.insert((a.min(b), a.max(b))) {
+                let dist = g.dist(a, b);
+                expanded.add_edge(a, b, dist);
+                expanded.add_edge(b, a, dist);
+            }
+        }
+    }
+
+    let nodes: Vec<G::Node> = expanded.nodes().collect();
+    let mut tree = spanning_forest(&expanded, &nodes);
+    prune_leaves(&mut tree, terminals);
+    tree
+}
+
+/// Repeatedly removes edges hanging off a non-terminal leaf.
+fn prune_leaves<N, D>(tree: &mut Vec<(N, N, D)>, terminals: &[N])
+where
+    N: Hash + Eq + Copy,
+{
+    let terminals: HashSet<N> = terminals.iter().copied().collect();
+    loop {
+        let mut degree: HashMap<N, usize> = HashMap::new();
+        for &(a, b, _) in tree.iter() {
+            *degree.entry(a).or_default() += 1;
+            *degree.entry(b).or_default() += 1;
+        }
+        let is_dangling = |n: &N| degree[n] == 1 && !terminals.contains(n);
+
+        let before = tree.len();
+        tree.retain(|(a, b, _)| !is_dangling(a) && !is_dangling(b));
+        if tree.len() == before {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SymmetricWrapper;
+
+    fn undirected(
+        edges: &[(usize, usize, u32)],
+    ) -> SymmetricWrapper<AdjacencyListGraph<usize, u32>> {
+        let mut g = AdjacencyListGraph::new();
+        for &(a, b, d) in edges {
+            g.add_edge(a, b, d);
+        }
+        SymmetricWrapper::new(g)
+    }
+
+    fn cost(tree: &[(usize, usize, u32)]) -> u32 {
+        tree.iter().map(|&(_, _, d)| d).sum()
+    }
+
+    #[test]
+    fn connects_terminals_through_a_hub() {
+        // Terminals 1, 2 and 3 are cheapest to connect through the hub 0, optimum is 6.
+        let g = undirected(&[
+            (0, 1, 2),
+            (0, 2, 2),
+            (0, 3, 2),
+            (1, 2, 5),
+            (2, 3, 5),
+            (1, 3, 5),
+            (3, 4, 1),
+        ]);
+        let tree = steiner_tree_approx(&g, &[1, 2, 3]);
+        assert_eq!(6, cost(&tree));
+        assert_eq!(3, tree.len());
+        assert!(tree.iter().all(|&(a, b, _)| a != 4 && b != 4));
+    }
+
+    #[test]
+    fn stays_within_twice_the_optimum() {
+        // Optimum goes through the hub at 0 for a cost of 3, but the shortest paths between
+        // terminals tie with routes around the outside.
+        let g = undirected(&[
+            (0, 1, 1),
+            (0, 2, 1),
+            (0, 3, 1),
+            (1, 5, 1),
+            (5, 2, 1),
+            (2, 6, 1),
+            (6, 3, 1),
+        ]);
+        let tree = steiner_tree_approx(&g, &[1, 2, 3]);
+        let cost = cost(&tree);
+        assert!((3..=6).contains(&cost));
+        for terminal in [1, 2, 3] {
+            assert!(tree.iter().any(|&(a, b, _)| a == terminal || b == terminal));
+        }
+    }
+
+    #[test]
+    fn single_terminal_needs_no_edges() {
+        let g = undirected(&[(1, 2, 1)]);
+        assert!(steiner_tree_approx(&g, &[1]).is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{ReversibleVGraph, UndirectedVGraph, VGraph};
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// Makes a directed graph undirected by adding the reverse of every edge.
+///
+/// When both directions of an edge already exist with different distances, the smaller one is
+/// used both ways.
+pub struct SymmetricWrapper<G> {
+    g: G,
+}
+
+impl<G> SymmetricWrapper<G> {
+    pub fn new(g: G) -> Self {
+        SymmetricWrapper { g }
+    }
+}
+
+impl<G> VGraph for SymmetricWrapper<G>
+where
+    G: ReversibleVGraph,
+    G::Node: Eq + Hash + Copy,
+    G::Dist: Ord,
+{
+    type Node = G::Node;
+
+    type Dist = G::Dist;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        let mut seen = HashSet::new();
+        let mut neighbors = self.g.out_edges(node);
+        neighbors.extend(self.g.in_edges(node));
+        neighbors.retain(|next| seen.insert(*next));
+        neighbors
+    }
+
+    fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+        let forward = self
+            .g
+            .out_edges(from)
+            .contains(&to)
+            .then(|| self.g.dist(from, to));
+        let backward = self
+            .g
+            .out_edges(to)
+            .contains(&from)
+            .then(|| self.g.dist(to, from));
+        match (forward, backward) {
+            (Some(f), Some(b)) => f.min(b),
+            (Some(d), None) | (None, Some(d)) => d,
+            (None, None) => panic!("dist is only defined for nodes connected by an edge."),
+        }
+    }
+
+    fn is_terminal(&self, node: Self::Node) -> bool {
+        self.g.is_terminal(node) && self.g.in_edges(node).is_empty()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.g.size_hint()
+    }
+}
+
+impl<G> ReversibleVGraph for SymmetricWrapper<G>
+where
+    G: ReversibleVGraph,
+    G::Node: Eq + Hash + Copy,
+    G::Dist: Ord,
+{
+    fn in_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.out_edges(node)
+    }
+}
+
+impl<G> UndirectedVGraph for SymmetricWrapper<G>
+where
+    G: ReversibleVGraph,
+    G::Node: Eq + Hash + Copy,
+    G::Dist: Ord,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minimum_spanning_tree;
+    use crate::test_graphs::Cycles;
+
+    #[test]
+    fn adds_reverse_edges() {
+        let g = SymmetricWrapper::new(Cycles {});
+        assert_eq!(vec![2, 3, 5, 10], g.out_edges(1));
+        assert_eq!(vec![1, 3, 4], g.out_edges(5));
+        // 3 -> 1 does not exist, so the distance comes from 1 -> 3.
+        assert_eq!(1, g.dist(3, 1));
+        // Both directions exist between 2 and 3, the cheaper wins.
+        assert_eq!(1, g.dist(3, 2));
+    }
+
+    #[test]
+    fn minimum_spanning_tree_of_wrapped_cycles() {
+        let nodes: Vec<usize> = (1..=10).collect();
+        let tree = minimum_spanning_tree(SymmetricWrapper::new(Cycles {}), &nodes);
+        assert_eq!(9, tree.len());
+        assert_eq!(9, tree.iter().map(|&(_, _, d)| d).sum::<usize>());
+    }
+}
@@ -0,0 +1,130 @@
+//! Small graphs shared by the tests of every module.
+use crate::{LabeledVGraph, ReversibleVGraph, VGraph};
+
+pub struct Ex {}
+impl Ex {
+    pub fn new() -> Self {
+        Ex {}
+    }
+}
+
+impl VGraph for Ex {
+    type Node = usize;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        match node {
+            1 => vec![2],
+            2 => vec![3],
+            3 => vec![],
+            _ => vec![],
+        }
+    }
+
+    type Dist = i32;
+
+    // If a node is in the graph, then the distance is 1
+    fn dist(&self, _: Self::Node, _: Self::Node) -> Self::Dist {
+        1
+    }
+}
+
+pub struct Cycles {}
+
+impl VGraph for Cycles {
+    type Node = usize;
+
+    type Dist = usize;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        match node {
+            1 => vec![2, 3],
+            2 => vec![3, 6],
+            3 => vec![4, 5],
+            4 => vec![10, 5],
+            5 => vec![1],
+            6 => vec![2],
+            7 => vec![8],
+            8 => vec![9],
+            9 => vec![10],
+            10 => vec![1],
+            _ => vec![],
+        }
+    }
+
+    fn dist(&self, from: Self::Node, _to: Self::Node) -> Self::Dist {
+        match from {
+            3 => 3,
+            _ => 1,
+        }
+    }
+}
+
+impl ReversibleVGraph for Cycles {
+    fn in_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        (1..=10)
+            .filter(|&from| self.out_edges(from).contains(&node))
+            .collect()
+    }
+}
+
+/// Tiles in a ring, moving left or right adds the value of the tile moved onto to the sum.
+pub struct RingPuzzle {
+    pub spaces: Vec<i32>,
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
+pub struct RingState {
+    pub position: usize,
+    pub sum: i32,
+}
+
+impl RingPuzzle {
+    pub fn left(&self, node: RingState) -> RingState {
+        let position = if node.position == 0 {
+            self.spaces.len() - 1
+        } else {
+            node.position - 1
+        };
+        RingState {
+            position,
+            sum: node.sum + self.spaces[position],
+        }
+    }
+
+    pub fn right(&self, node: RingState) -> RingState {
+        let position = (node.position + 1) % self.spaces.len();
+        RingState {
+            position,
+            sum: node.sum + self.spaces[position],
+        }
+    }
+}
+
+impl VGraph for RingPuzzle {
+    type Node = RingState;
+
+    type Dist = usize;
+
+    fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+        // Negative sums end the game. No outgoing states.
+        if node.sum < 0 {
+            return vec![];
+        }
+        vec![self.left(node), self.right(node)]
+    }
+
+    fn dist(&self, _from: Self::Node, _to: Self::Node) -> Self::Dist {
+        1
+    }
+}
+
+impl LabeledVGraph for RingPuzzle {
+    type EdgeData = &'static str;
+
+    fn out_edges_with_data(&self, node: Self::Node) -> Vec<(Self::Node, Self::EdgeData)> {
+        if node.sum < 0 {
+            return vec![];
+        }
+        vec![(self.left(node), "left"), (self.right(node), "right")]
+    }
+}
@@ -0,0 +1,135 @@
+use crate::partial::TotalOrder;
+use crate::{GridGraph, VGraph};
+use priority_queue::PriorityQueue;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+type Cell = (usize, usize);
+
+/// Any-angle path from `start` to `end` on a grid, using Lazy Theta*.
+///
+/// Unlike A* over the grid's edges, a step may go straight to any cell in line of sight, so the
+/// path is not limited to horizontal and vertical moves. Returns the cells where the path turns,
+/// from `start` to `end`, and its euclidean length. Line of sight is only checked when a cell is
+/// taken off the frontier, the lazy part, which saves most of the checks plain Theta* does.
+///
+/// The result is usually, but not always, the true shortest any-angle path.
+pub fn theta_star(grid: &GridGraph, start: Cell, end: Cell) -> Option<(Vec<Cell>, f64)> {
+    if !grid.is_open(start) || !grid.is_open(end) {
+        return None;
+    }
+
+    let mut to_explore = PriorityQueue::new();
+    let mut dist_from_start: HashMap<Cell, f64> = HashMap::new();
+    let mut parent: HashMap<Cell, Cell> = HashMap::new();
+    let mut closed: HashSet<Cell> = HashSet::new();
+    dist_from_start.insert(start, 0.0);
+    parent.insert(start, start);
+    to_explore.push(start, Reverse(TotalOrder(euclidean(start, end))));
+
+    while let Some((cur, _)) = to_explore.pop() {
+        closed.insert(cur);
+
+        // The parent was picked assuming line of sight, fall back to the best grid neighbor.
+        if !grid.line_of_sight(parent[&cur], cur) {
+            let (best, dist) = grid
+                .out_edges(cur)
+                .into_iter()
+                .filter(|n| closed.contains(n))
+                .map(|n| (n, dist_from_start[&n] + 1.0))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .expect("A cell is only reached from a closed neighbor.");
+            parent.insert(cur, best);
+            dist_from_start.insert(cur, dist);
+        }
+
+        if cur == end {
+            return Some((trace_parents(&parent, end), dist_from_start[&end]));
+        }
+
+        let cur_parent = parent[&cur];
+        for next in grid.out_edges(cur) {
+            if closed.contains(&next) {
+                continue;
+            }
+            let through_parent = dist_from_start[&cur_parent] + euclidean(cur_parent, next);
+            if dist_from_start
+                .get(&next)
+                .is_some_and(|&best| through_parent >= best)
+            {
+                continue;
+            }
+            dist_from_start.insert(next, through_parent);
+            parent.insert(next, cur_parent);
+            to_explore.push(
+                next,
+                Reverse(TotalOrder(through_parent + euclidean(next, end))),
+            );
+        }
+    }
+
+    None
+}
+
+fn euclidean(a: Cell, b: Cell) -> f64 {
+    let dx = a.0 as f64 - b.0 as f64;
+    let dy = a.1 as f64 - b.1 as f64;
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Follows parents from `end` back to the cell that is its own parent.
+fn trace_parents(parent: &HashMap<Cell, Cell>, end: Cell) -> Vec<Cell> {
+    let mut path = vec![end];
+    let mut cur = end;
+    while parent[&cur] != cur {
+        cur = parent[&cur];
+        path.push(cur);
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{a_star_search, path_length};
+
+    #[test]
+    fn straight_line_in_open_grid() {
+        let grid = GridGraph::new(10, 10);
+        let (path, length) = theta_star(&grid, (0, 0), (9, 4)).unwrap();
+        assert_eq!(vec![(0, 0), (9, 4)], path);
+        assert!((length - 97f64.sqrt()).abs() < 1e-9);
+
+        let grid_path = a_star_search(&grid, (0, 0), |n| n == (9, 4), |_| 0).unwrap();
+        assert_eq!(13, path_length(&grid, grid_path));
+        assert!(length < 13.0);
+    }
+
+    #[test]
+    fn bends_around_walls() {
+        let mut grid = GridGraph::new(7, 7);
+        for y in 0..6 {
+            grid.block((3, y));
+        }
+        let (path, length) = theta_star(&grid, (0, 0), (6, 0)).unwrap();
+        assert_eq!((0, 0), path[0]);
+        assert_eq!((6, 0), *path.last().unwrap());
+        for leg in path.windows(2) {
+            assert!(grid.line_of_sight(leg[0], leg[1]), "{leg:?}");
+        }
+
+        let grid_path = a_star_search(&grid, (0, 0), |n| n == (6, 0), |_| 0).unwrap();
+        assert!(length < path_length(&grid, grid_path) as f64);
+        assert!(length > euclidean((0, 0), (6, 0)));
+    }
+
+    #[test]
+    fn unreachable_end() {
+        let mut grid = GridGraph::new(3, 3);
+        grid.block((1, 0));
+        grid.block((1, 1));
+        grid.block((1, 2));
+        assert_eq!(None, theta_star(&grid, (0, 0), (2, 2)));
+    }
+}
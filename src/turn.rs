@@ -0,0 +1,140 @@
+use crate::VGraph;
+use num::traits::Zero;
+
+/// Wraps a graph so that changing direction costs an extra `penalty`.
+///
+/// A node is a `(prev, cur)` pair of the node we came from and the node we are on, `prev` is
+/// `None` at the start. Moving on adds `penalty` to the wrapped distance whenever `direction`
+/// gives a different answer for the edge taken than for the edge that led here. Start a search
+/// at `start` and turn the result back into plain nodes with `original_path`.
+///
+/// Every node of the wrapped graph appears once per neighbor it can be entered from, so the
+/// state space is about as large as the number of edges.
+pub struct TurnPenaltyGraph<G, F>
+where
+    G: VGraph,
+{
+    g: G,
+    penalty: G::Dist,
+    direction: F,
+}
+
+impl<G, F> TurnPenaltyGraph<G, F>
+where
+    G: VGraph,
+{
+    pub fn new(g: G, penalty: G::Dist, direction: F) -> Self {
+        TurnPenaltyGraph {
+            g,
+            penalty,
+            direction,
+        }
+    }
+
+    /// The node to start a search at `node` from.
+    pub fn start(&self, node: G::Node) -> (Option<G::Node>, G::Node) {
+        (None, node)
+    }
+
+    /// The nodes of the wrapped graph visited along `path`.
+    pub fn original_path(&self, path: Vec<(Option<G::Node>, G::Node)>) -> Vec<G::Node> {
+        path.into_iter().map(|(_, cur)| cur).collect()
+    }
+}
+
+impl<G, F, D> VGraph for TurnPenaltyGraph<G, F>
+where
+    G: VGraph,
+    G::Node: Copy,
+    G::Dist: Zero + Copy,
+    F: Fn(G::Node, G::Node) -> D,
+    D: PartialEq,
+{
+    type Node = (Option<G::Node>, G::Node);
+
+    type Dist = G::Dist;
+
+    fn out_edges(&self, (_, cur): Self::Node) -> Vec<Self::Node> {
+        self.g
+            .out_edges(cur)
+            .into_iter()
+            .map(|next| (Some(cur), next))
+            .collect()
+    }
+
+    fn dist(&self, (prev, cur): Self::Node, (_, next): Self::Node) -> Self::Dist {
+        let dist = self.g.dist(cur, next);
+        match prev {
+            Some(prev) if (self.direction)(prev, cur) != (self.direction)(cur, next) => {
+                dist + self.penalty
+            }
+            _ => dist,
+        }
+    }
+
+    fn is_terminal(&self, (_, cur): Self::Node) -> bool {
+        self.g.is_terminal(cur)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{a_star_search, path_length, GridGraph};
+
+    type Cell = (usize, usize);
+
+    fn grid_direction(from: Cell, to: Cell) -> (isize, isize) {
+        (
+            to.0 as isize - from.0 as isize,
+            to.1 as isize - from.1 as isize,
+        )
+    }
+
+    /// A staircase that is the shortest way to (3, 3), and a longer route along the edges that
+    /// turns only twice.
+    fn stairs() -> GridGraph {
+        let mut g = GridGraph::new(5, 4);
+        let open = [(1, 1), (2, 1), (2, 2), (3, 2), (3, 3)];
+        for cell in g.clone().cells().collect::<Vec<_>>() {
+            let edge = cell.1 == 0 || cell.0 == 4;
+            if !edge && !open.contains(&cell) {
+                g.block(cell);
+            }
+        }
+        g
+    }
+
+    #[test]
+    fn turn_penalty_changes_route() {
+        let g = stairs();
+        let shortest = a_star_search(&g, (0, 0), |n| n == (3, 3), |_| 0).unwrap();
+        assert_eq!(6, path_length(&g, shortest));
+
+        let turns = TurnPenaltyGraph::new(&g, 3, grid_direction);
+        let path = a_star_search(&turns, turns.start((0, 0)), |(_, n)| n == (3, 3), |_| 0).unwrap();
+        assert_eq!(14, path_length(&turns, path.clone()));
+        assert_eq!(
+            vec![
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (3, 0),
+                (4, 0),
+                (4, 1),
+                (4, 2),
+                (4, 3),
+                (3, 3)
+            ],
+            turns.original_path(path)
+        );
+    }
+
+    #[test]
+    fn no_penalty_keeps_shortest_cost() {
+        let g = stairs();
+        let turns = TurnPenaltyGraph::new(&g, 0, grid_direction);
+        let path = a_star_search(&turns, (None, (0, 0)), |(_, n)| n == (3, 3), |_| 0).unwrap();
+        assert_eq!(6, path_length(&turns, path));
+    }
+}
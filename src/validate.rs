@@ -0,0 +1,182 @@
+use crate::{UndirectedVGraph, VGraph};
+use num::traits::Zero;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::Hash;
+
+/// A suspicious edge found by `validate`.
+///
+/// None of these stop a search from running, but they usually mean the graph is not the one its
+/// author intended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphWarning<N> {
+    /// The node has an edge to itself.
+    SelfLoop(N),
+    /// `to` is listed more than once in `out_edges(from)`.
+    DuplicateEdge { from: N, to: N },
+    /// The edge costs less than zero, which Dijkstra and A* do not support.
+    NegativeDistance { from: N, to: N },
+    /// The graph is undirected, but `to` has no edge back to `from`.
+    MissingReverseEdge { from: N, to: N },
+    /// The graph is undirected, but the edge costs differ in each direction.
+    AsymmetricDistance { from: N, to: N },
+}
+
+impl<N: fmt::Debug> fmt::Display for GraphWarning<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphWarning::SelfLoop(node) => write!(f, "node {node:?} has an edge to itself"),
+            GraphWarning::DuplicateEdge { from, to } => {
+                write!(f, "edge from {from:?} to {to:?} is listed more than once")
+            }
+            GraphWarning::NegativeDistance { from, to } => {
+                write!(f, "edge from {from:?} to {to:?} has a negative distance")
+            }
+            GraphWarning::MissingReverseEdge { from, to } => {
+                write!(f, "edge from {from:?} to {to:?} has no reverse edge")
+            }
+            GraphWarning::AsymmetricDistance { from, to } => {
+                write!(
+                    f,
+                    "edge from {from:?} to {to:?} costs something different in reverse"
+                )
+            }
+        }
+    }
+}
+
+/// Checks the edges out of each of `nodes` for self loops, duplicates and negative distances.
+///
+/// A diagnostic for graphs under construction, the warnings come in the order the edges are
+/// listed. Only the given nodes are scanned, so pass every node that matters.
+pub fn validate<G, I>(g: G, nodes: I) -> Vec<GraphWarning<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + PartialOrd,
+    I: IntoIterator<Item = G::Node>,
+{
+    let mut warnings = Vec::new();
+    for from in nodes {
+        check_edges_from(&g, from, &mut warnings);
+    }
+    warnings
+}
+
+/// `validate`, that also checks every edge has a reverse edge of the same distance as
+/// `UndirectedVGraph` promises.
+pub fn validate_undirected<G, I>(g: G, nodes: I) -> Vec<GraphWarning<G::Node>>
+where
+    G: UndirectedVGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + PartialOrd,
+    I: IntoIterator<Item = G::Node>,
+{
+    let mut warnings = Vec::new();
+    for from in nodes {
+        check_edges_from(&g, from, &mut warnings);
+        let mut seen = HashSet::new();
+        for to in g.out_edges(from) {
+            if !seen.insert(to) {
+                continue;
+            }
+            if !g.out_edges(to).contains(&from) {
+                warnings.push(GraphWarning::MissingReverseEdge { from, to });
+            } else if g.dist(from, to) != g.dist(to, from) {
+                warnings.push(GraphWarning::AsymmetricDistance { from, to });
+            }
+        }
+    }
+    warnings
+}
+
+/// Pushes the warnings that only depend on the edges out of `from`.
+fn check_edges_from<G>(g: &G, from: G::Node, warnings: &mut Vec<GraphWarning<G::Node>>)
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + PartialOrd,
+{
+    let mut seen = HashSet::new();
+    let mut duplicated = HashSet::new();
+    for to in g.out_edges(from) {
+        if !seen.insert(to) {
+            // Repeats of an edge are reported once, and were checked the first time.
+            if duplicated.insert(to) {
+                warnings.push(GraphWarning::DuplicateEdge { from, to });
+            }
+            continue;
+        }
+        if to == from {
+            warnings.push(GraphWarning::SelfLoop(from));
+        }
+        if g.dist(from, to) < G::Dist::zero() {
+            warnings.push(GraphWarning::NegativeDistance { from, to });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::Cycles;
+
+    /// Claims to be undirected, but has a mistake on almost every node.
+    struct Flawed {}
+
+    impl VGraph for Flawed {
+        type Node = u32;
+
+        type Dist = i32;
+
+        fn out_edges(&self, node: Self::Node) -> Vec<Self::Node> {
+            match node {
+                0 => vec![1, 1, 1],
+                1 => vec![0, 1, 2],
+                2 => vec![1, 3],
+                _ => vec![],
+            }
+        }
+
+        fn dist(&self, from: Self::Node, to: Self::Node) -> Self::Dist {
+            match (from, to) {
+                (2, 1) => 2,
+                (2, 3) => -1,
+                _ => 1,
+            }
+        }
+    }
+
+    impl UndirectedVGraph for Flawed {}
+
+    #[test]
+    fn clean_graph_has_no_warnings() {
+        assert_eq!(
+            Vec::<GraphWarning<usize>>::new(),
+            validate(Cycles {}, 1..=10)
+        );
+    }
+
+    #[test]
+    fn reports_each_flaw() {
+        assert_eq!(
+            vec![
+                GraphWarning::DuplicateEdge { from: 0, to: 1 },
+                GraphWarning::SelfLoop(1),
+                GraphWarning::NegativeDistance { from: 2, to: 3 },
+            ],
+            validate(Flawed {}, 0..4)
+        );
+        assert_eq!(
+            vec![
+                GraphWarning::DuplicateEdge { from: 0, to: 1 },
+                GraphWarning::SelfLoop(1),
+                GraphWarning::AsymmetricDistance { from: 1, to: 2 },
+                GraphWarning::NegativeDistance { from: 2, to: 3 },
+                GraphWarning::AsymmetricDistance { from: 2, to: 1 },
+                GraphWarning::MissingReverseEdge { from: 2, to: 3 },
+            ],
+            validate_undirected(Flawed {}, 0..4)
+        );
+    }
+}
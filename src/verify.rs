@@ -0,0 +1,96 @@
+use crate::{distance, VGraph, VerificationError};
+use num::traits::Zero;
+use std::hash::Hash;
+
+/// Checks that `path` is a shortest path between its first and last node and costs
+/// `claimed_cost`.
+///
+/// Every step must be an edge of the graph, and the total must match `claimed_cost`. An
+/// independent Dijkstra search then confirms nothing cheaper exists. Meant for testing search
+/// results, for example on randomly generated graphs.
+pub fn verify_shortest_path<G>(
+    g: G,
+    path: &[G::Node],
+    claimed_cost: G::Dist,
+) -> Result<(), VerificationError<G::Node, G::Dist>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + Ord + Copy,
+{
+    let (Some(&start), Some(&end)) = (path.first(), path.last()) else {
+        return Err(VerificationError::EmptyPath);
+    };
+
+    let mut actual = G::Dist::zero();
+    for step in path.windows(2) {
+        let (from, to) = (step[0], step[1]);
+        if !g.out_edges(from).contains(&to) {
+            return Err(VerificationError::MissingEdge { from, to });
+        }
+        actual = actual + g.dist(from, to);
+    }
+    if actual != claimed_cost {
+        return Err(VerificationError::WrongCost {
+            claimed: claimed_cost,
+            actual,
+        });
+    }
+
+    let shortest = distance(&g, start, end).expect("The path itself reaches the end.");
+    if shortest < claimed_cost {
+        return Err(VerificationError::NotShortest {
+            claimed: claimed_cost,
+            shortest,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::{random_graph, SplitMix64};
+    use crate::test_graphs::Cycles;
+    use crate::{a_star_search, path_length};
+
+    #[test]
+    fn detects_each_problem() {
+        assert_eq!(Ok(()), verify_shortest_path(Cycles {}, &[1, 3, 4, 10], 5));
+        assert_eq!(
+            Err(VerificationError::EmptyPath),
+            verify_shortest_path(Cycles {}, &[], 0)
+        );
+        assert_eq!(
+            Err(VerificationError::MissingEdge { from: 3, to: 10 }),
+            verify_shortest_path(Cycles {}, &[1, 3, 10], 2)
+        );
+        assert_eq!(
+            Err(VerificationError::WrongCost {
+                claimed: 4,
+                actual: 5
+            }),
+            verify_shortest_path(Cycles {}, &[1, 3, 4, 10], 4)
+        );
+        assert_eq!(
+            Err(VerificationError::NotShortest {
+                claimed: 6,
+                shortest: 5
+            }),
+            verify_shortest_path(Cycles {}, &[1, 2, 3, 4, 10], 6)
+        );
+    }
+
+    #[test]
+    fn a_star_on_random_graphs() {
+        let mut rng = SplitMix64::new(17);
+        for seed in 0..50 {
+            let n = 2 + rng.below(30) as usize;
+            let g = random_graph(n, 1 + rng.below(4) as usize, seed);
+            let (start, end) = (rng.below(n as u64) as usize, rng.below(n as u64) as usize);
+            let path = a_star_search(&g, start, |node| node == end, |_| 0).unwrap();
+            let cost = path_length(&g, path.clone());
+            assert_eq!(Ok(()), verify_shortest_path(&g, &path, cost), "seed {seed}");
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{back_track, SearchError, VGraph};
+use num::traits::Zero;
+use priority_queue::PriorityQueue;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A* search that starts out knowing the distance from `start` to some nodes.
+///
+/// `seed` maps nodes to their true distance from `start`, such as the distances settled by an
+/// earlier search from the same start. A path reaching a seeded node for more than its seeded
+/// distance is dropped right away instead of being explored, which saves expanding nodes twice
+/// when the heuristic is admissible but not consistent.
+///
+/// Returns `SearchError::InconsistentSeed` when the seed gives `start` a distance other than zero,
+/// or when the search finds a shorter path to a seeded node than its seed claims. A seeded
+/// distance shorter than the true one can not be noticed, and hides every path through that node.
+pub fn a_star_warm<G, F, H>(
+    g: G,
+    start: G::Node,
+    is_end: F,
+    heuristic: H,
+    seed: HashMap<G::Node, G::Dist>,
+) -> Result<Option<Vec<G::Node>>, SearchError<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Ord + Copy,
+    G::Dist: Zero + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+    H: Fn(G::Node) -> G::Dist,
+{
+    if seed.get(&start).is_some_and(|d| !d.is_zero()) {
+        return Err(SearchError::InconsistentSeed(start));
+    }
+
+    let mut to_explore = PriorityQueue::new();
+    to_explore.push(start, Reverse(heuristic(start)));
+    let mut prev: HashMap<G::Node, G::Node> = HashMap::new();
+    let mut dist_from_start: HashMap<G::Node, G::Dist> = HashMap::new();
+    dist_from_start.insert(start, G::Dist::zero());
+
+    while let Some((cur, _priority)) = to_explore.pop() {
+        if is_end(cur) {
+            return Ok(Some(back_track(&prev, cur)));
+        }
+
+        let cur_distance = dist_from_start[&cur];
+        for next in g.out_edges(cur) {
+            let start_to_next = cur_distance + g.dist(cur, next);
+            if let Some(&known) = seed.get(&next) {
+                if start_to_next < known {
+                    return Err(SearchError::InconsistentSeed(next));
+                }
+                if start_to_next > known {
+                    continue;
+                }
+            }
+            if dist_from_start
+                .get(&next)
+                .is_some_and(|&best| start_to_next >= best)
+            {
+                continue;
+            }
+            to_explore.push_increase(next, Reverse(start_to_next + heuristic(next)));
+            prev.insert(next, cur);
+            dist_from_start.insert(next, start_to_next);
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_graphs::Cycles;
+    use crate::{a_star_search, dijkstra_iter, AdjacencyListGraph, ExpansionRecorder, GraphCall};
+
+    /// An admissible but inconsistent heuristic makes A* reach 3 the long way first, then
+    /// explore it again once the short way through 1 is found.
+    fn reopening() -> (AdjacencyListGraph<usize, u32>, impl Fn(usize) -> u32) {
+        let mut g = AdjacencyListGraph::new();
+        g.add_edge(0, 1, 1);
+        g.add_edge(0, 2, 1);
+        g.add_edge(1, 3, 1);
+        g.add_edge(2, 3, 3);
+        g.add_edge(3, 4, 3);
+        (g, |n| if n == 1 { 4 } else { 0 })
+    }
+
+    fn expansions<G: VGraph>(recorder: ExpansionRecorder<G>) -> usize {
+        recorder
+            .into_log()
+            .iter()
+            .filter(|call| matches!(call, GraphCall::OutEdges { .. }))
+            .count()
+    }
+
+    #[test]
+    fn seed_from_earlier_search_saves_expansions() {
+        let (g, heuristic) = reopening();
+        let seed: HashMap<usize, u32> = dijkstra_iter(&g, 0).collect();
+
+        let cold = ExpansionRecorder::new(&g);
+        let cold_path = a_star_search(&cold, 0, |n| n == 4, &heuristic).unwrap();
+        let warm = ExpansionRecorder::new(&g);
+        let warm_path = a_star_warm(&warm, 0, |n| n == 4, &heuristic, seed).unwrap();
+
+        assert_eq!(vec![0, 1, 3, 4], cold_path);
+        assert_eq!(Some(cold_path), warm_path);
+        assert_eq!(5, expansions(cold));
+        assert_eq!(4, expansions(warm));
+    }
+
+    #[test]
+    fn partial_seed_matches_cold_search() {
+        let seed: HashMap<usize, usize> = dijkstra_iter(Cycles {}, 1).take(4).collect();
+        for end in 1..=10 {
+            assert_eq!(
+                Ok(a_star_search(Cycles {}, 1, |n| n == end, |_| 0)),
+                a_star_warm(Cycles {}, 1, |n| n == end, |_| 0, seed.clone()),
+                "to {end}"
+            );
+        }
+    }
+
+    #[test]
+    fn inconsistent_seeds() {
+        let nonzero_start = HashMap::from([(1, 2)]);
+        assert_eq!(
+            Err(SearchError::InconsistentSeed(1)),
+            a_star_warm(Cycles {}, 1, |n| n == 10, |_| 0, nonzero_start)
+        );
+
+        let too_far = HashMap::from([(1, 0), (3, 5)]);
+        assert_eq!(
+            Err(SearchError::InconsistentSeed(3)),
+            a_star_warm(Cycles {}, 1, |n| n == 10, |_| 0, too_far)
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{back_track, VGraph};
+use num::traits::{One, Zero};
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Shortest path search for graphs where every edge costs either 0 or 1.
+///
+/// Free edges go to the front of a `VecDeque` and the rest to the back, which keeps the queue
+/// sorted without the cost of a priority queue. `dist` must only ever return 0 or 1, any other
+/// cost gives wrong paths and panics when debug assertions are enabled.
+pub fn zero_one_bfs<G, F>(g: G, start: G::Node, is_end: F) -> Option<Vec<G::Node>>
+where
+    G: VGraph,
+    G::Node: Hash + Eq + Copy,
+    G::Dist: Zero + One + Ord + Copy,
+    F: Fn(G::Node) -> bool,
+{
+    let mut to_explore = VecDeque::new();
+    to_explore.push_back((start, G::Dist::zero()));
+    let mut dist_from_start: HashMap<G::Node, G::Dist> = HashMap::new();
+    dist_from_start.insert(start, G::Dist::zero());
+    let mut prev: HashMap<G::Node, G::Node> = HashMap::new();
+
+    while let Some((cur, cur_distance)) = to_explore.pop_front() {
+        // A node is queued again each time its distance improves, skip the outdated entries.
+        if dist_from_start[&cur] < cur_distance {
+            continue;
+        }
+        if is_end(cur) {
+            return Some(back_track(&prev, cur));
+        }
+
+        for next in g.out_edges(cur) {
+            let cost = g.dist(cur, next);
+            debug_assert!(
+                cost.is_zero() || cost.is_one(),
+                "zero_one_bfs requires every edge to cost 0 or 1"
+            );
+            let start_to_next = cur_distance + cost;
+            let improves = dist_from_start
+                .get(&next)
+                .map(|&best| start_to_next < best)
+                .unwrap_or(true);
+            if improves {
+                dist_from_start.insert(next, start_to_next);
+                prev.insert(next, cur);
+                if cost.is_zero() {
+                    to_explore.push_front((next, start_to_next));
+                } else {
+                    to_explore.push_back((next, start_to_next));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{distance, path_length, FnGraph};
+
+    #[test]
+    fn matches_dijkstra() {
+        // Stepping up costs 1, doubling is free.
+        let g = FnGraph::new(
+            |n: u32| [n + 1, 2 * n].into_iter().filter(|&m| m <= 100).collect(),
+            |from: u32, to: u32| u32::from(to == from + 1 && to != 2 * from),
+        );
+        for end in 1..=100 {
+            let path = zero_one_bfs(&g, 1, |n| n == end).unwrap();
+            assert_eq!(Some(&1), path.first());
+            assert_eq!(Some(&end), path.last());
+            assert_eq!(distance(&g, 1, end), Some(path_length(&g, path)), "{end}");
+        }
+        assert_eq!(None, zero_one_bfs(&g, 1, |n| n == 0));
+    }
+}